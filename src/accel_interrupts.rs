@@ -0,0 +1,191 @@
+use maybe_async_cfg::maybe;
+
+use crate::{
+    interface::{ReadData, WriteData},
+    register_address::{
+        ActDurA, ActThsA, ClickSrcA, ClickThsA, CtrlReg6A, Int1DurationA, Int1SrcA, Int1ThsA,
+        Int2CfgA, Int2DurationA, Int2SrcA, Int2ThsA, TimeLatencyA, TimeLimitA, TimeWindowA,
+    },
+    ClickConfig, ClickSource, Error, InertialInterruptConfig, InterruptGenerator, InterruptSource,
+    Lsm303agr,
+};
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE, MODE> Lsm303agr<DI, MODE>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    /// Configure an accelerometer inertial interrupt generator.
+    ///
+    /// The threshold is converted from m*g* using the current accelerometer scale.
+    ///
+    /// To route the interrupt to the INT1 pin, use
+    /// [`acc_enable_interrupt()`](Lsm303agr::acc_enable_interrupt) with
+    /// [`Interrupt::Aoi1`](crate::Interrupt::Aoi1) or [`Interrupt::Aoi2`](crate::Interrupt::Aoi2).
+    pub async fn acc_set_inertial_interrupt(
+        &mut self,
+        generator: InterruptGenerator,
+        config: InertialInterruptConfig,
+    ) -> Result<(), Error<CommE>> {
+        let threshold = self
+            .ctrl_reg4_a
+            .scale()
+            .threshold_from_mg(config.threshold_mg);
+        let duration = config.duration.min(0x7F);
+        let cfg = config.events.with_mode(config.mode);
+
+        match generator {
+            InterruptGenerator::Aoi1 => {
+                self.iface
+                    .write_accel_register(Int1ThsA::from_bits_truncate(threshold))
+                    .await?;
+                self.iface
+                    .write_accel_register(Int1DurationA::from_bits_truncate(duration))
+                    .await?;
+            }
+            InterruptGenerator::Aoi2 => {
+                self.iface
+                    .write_accel_register(Int2ThsA::from_bits_truncate(threshold))
+                    .await?;
+                self.iface
+                    .write_accel_register(Int2DurationA::from_bits_truncate(duration))
+                    .await?;
+            }
+        }
+
+        let reg5 = self
+            .ctrl_reg5_a
+            .with_inertial_interrupt(generator, config.latched, config.mode);
+        self.iface.write_accel_register(reg5).await?;
+        self.ctrl_reg5_a = reg5;
+
+        match generator {
+            InterruptGenerator::Aoi1 => self.iface.write_accel_register(cfg).await,
+            InterruptGenerator::Aoi2 => {
+                self.iface
+                    .write_accel_register(Int2CfgA::from_bits_truncate(cfg.bits()))
+                    .await
+            }
+        }
+    }
+
+    /// Read the source of an accelerometer inertial interrupt generator.
+    ///
+    /// Reading the source clears a latched interrupt.
+    pub async fn read_interrupt_source(
+        &mut self,
+        generator: InterruptGenerator,
+    ) -> Result<InterruptSource, Error<CommE>> {
+        let src = match generator {
+            InterruptGenerator::Aoi1 => self.iface.read_accel_register::<Int1SrcA>().await?,
+            InterruptGenerator::Aoi2 => {
+                let src = self.iface.read_accel_register::<Int2SrcA>().await?;
+                Int1SrcA::from_bits_truncate(src.bits())
+            }
+        };
+
+        Ok(InterruptSource::new(src))
+    }
+
+    /// Configure the accelerometer click (tap) detection.
+    ///
+    /// The threshold is converted from m*g* using the current accelerometer scale
+    /// and the timings are converted from milliseconds using the current output data rate.
+    ///
+    /// Returns `Error::InvalidInputData` if the accelerometer is powered down.
+    ///
+    /// To route the interrupt to a pin, use
+    /// [`acc_enable_interrupt_on()`](Lsm303agr::acc_enable_interrupt_on) with
+    /// [`Interrupt::Click`](crate::Interrupt::Click).
+    pub async fn acc_set_click_detection(
+        &mut self,
+        config: ClickConfig,
+    ) -> Result<(), Error<CommE>> {
+        let odr = self.accel_odr.ok_or(Error::InvalidInputData)?;
+
+        let threshold = self
+            .ctrl_reg4_a
+            .scale()
+            .threshold_from_mg(config.threshold_mg);
+        let mut ths = ClickThsA::from_bits_truncate(threshold);
+        ths.set(ClickThsA::LIR_CLICK, config.latched);
+        self.iface.write_accel_register(ths).await?;
+
+        let time_limit = odr.samples_from_ms(config.time_limit_ms, TimeLimitA::TLI.bits());
+        self.iface
+            .write_accel_register(TimeLimitA::from_bits_truncate(time_limit))
+            .await?;
+
+        let time_latency = odr.samples_from_ms(config.time_latency_ms, TimeLatencyA::TLA.bits());
+        self.iface
+            .write_accel_register(TimeLatencyA::from_bits_truncate(time_latency))
+            .await?;
+
+        let time_window = odr.samples_from_ms(config.time_window_ms, TimeWindowA::TW.bits());
+        self.iface
+            .write_accel_register(TimeWindowA::from_bits_truncate(time_window))
+            .await?;
+
+        self.iface.write_accel_register(config.clicks).await
+    }
+
+    /// Read the accelerometer click (tap) source.
+    ///
+    /// Reading the source clears a latched interrupt.
+    pub async fn click_source(&mut self) -> Result<ClickSource, Error<CommE>> {
+        self.iface
+            .read_accel_register::<ClickSrcA>()
+            .await
+            .map(ClickSource::new)
+    }
+
+    /// Configure the accelerometer sleep-to-wake and return-to-sleep function.
+    ///
+    /// When the acceleration stays below `threshold_mg` for the given duration,
+    /// the accelerometer automatically switches to low-power mode at 10 Hz.
+    /// As soon as the acceleration exceeds the threshold, it returns to the
+    /// configured mode and output data rate.
+    ///
+    /// The threshold is converted from m*g* using the current accelerometer scale
+    /// and clamped to the maximum of 127 LSB.
+    /// The duration is given in units of 8 samples: (8 · `duration` + 1) / ODR.
+    ///
+    /// [`get_accel_mode()`](Lsm303agr::get_accel_mode) keeps reporting the configured
+    /// mode while the accelerometer is inactive.
+    ///
+    /// To route the activity status to the INT2 pin, use
+    /// [`acc_route_activity_to_int2()`](Lsm303agr::acc_route_activity_to_int2).
+    pub async fn set_activity_detection(
+        &mut self,
+        threshold_mg: u16,
+        duration: u8,
+    ) -> Result<(), Error<CommE>> {
+        let threshold = self.ctrl_reg4_a.scale().threshold_from_mg(threshold_mg);
+
+        self.iface
+            .write_accel_register(ActDurA::from_bits_truncate(duration))
+            .await?;
+        self.iface
+            .write_accel_register(ActThsA::from_bits_truncate(threshold))
+            .await
+    }
+
+    /// Disable the accelerometer sleep-to-wake and return-to-sleep function.
+    pub async fn disable_activity_detection(&mut self) -> Result<(), Error<CommE>> {
+        self.iface.write_accel_register(ActThsA::empty()).await
+    }
+
+    /// Enable or disable routing the accelerometer activity status to the INT2 pin.
+    pub async fn acc_route_activity_to_int2(&mut self, enable: bool) -> Result<(), Error<CommE>> {
+        let mut reg6 = self.ctrl_reg6_a;
+        reg6.set(CtrlReg6A::P2_ACT, enable);
+
+        self.iface.write_accel_register(reg6).await?;
+        self.ctrl_reg6_a = reg6;
+
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use maybe_async_cfg::maybe;
 use crate::{
     interface::{ReadData, WriteData},
     register_address::{CtrlReg1A, CtrlReg4A},
-    AccelMode, AccelOutputDataRate, AccelScale, Error, Lsm303agr,
+    AccelMode, AccelOutputDataRate, AccelScale, AxisSet, Endianness, Error, Lsm303agr,
 };
 
 #[maybe(
@@ -77,20 +77,12 @@ where
     }
 
     /// Get the accelerometer mode
+    ///
+    /// This is the configured mode, also while the accelerometer is temporarily
+    /// in low-power mode due to inactivity. See
+    /// [`set_activity_detection()`](Lsm303agr::set_activity_detection).
     pub async fn get_accel_mode(&mut self) -> AccelMode {
-        let power_down = self.ctrl_reg1_a.intersection(CtrlReg1A::ODR).is_empty();
-        let lp_enabled = self.ctrl_reg1_a.contains(CtrlReg1A::LPEN);
-        let hr_enabled = self.ctrl_reg4_a.contains(CtrlReg4A::HR);
-
-        if power_down {
-            AccelMode::PowerDown
-        } else if hr_enabled {
-            AccelMode::HighResolution
-        } else if lp_enabled {
-            AccelMode::LowPower
-        } else {
-            AccelMode::Normal
-        }
+        AccelMode::from_registers(self.ctrl_reg1_a, self.ctrl_reg4_a)
     }
 
     /// Set accelerometer scaling factor
@@ -109,6 +101,47 @@ where
     pub async fn get_accel_scale(&self) -> AccelScale {
         self.ctrl_reg4_a.scale()
     }
+
+    /// Enable the given accelerometer axes and disable the rest.
+    ///
+    /// Disabling unused axes reduces the power consumption.
+    /// All axes are enabled by default.
+    pub async fn set_accel_axes(&mut self, axes: AxisSet) -> Result<(), Error<CommE>> {
+        let reg1 = self.ctrl_reg1_a.with_axes(axes);
+        self.iface.write_accel_register(reg1).await?;
+        self.ctrl_reg1_a = reg1;
+        Ok(())
+    }
+
+    /// Get the enabled accelerometer axes.
+    pub async fn get_accel_axes(&self) -> AxisSet {
+        self.ctrl_reg1_a.axes()
+    }
+
+    /// Set the accelerometer output data endianness.
+    ///
+    /// According to the [datasheet], the selection only takes effect in
+    /// high-resolution mode. In other modes the output data is little endian.
+    ///
+    /// The measurements are decoded according to this setting and the current mode.
+    ///
+    /// [datasheet]: https://www.st.com/resource/en/datasheet/lsm303agr.pdf
+    pub async fn set_accel_endianness(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<(), Error<CommE>> {
+        let reg4 = self.ctrl_reg4_a.with_endianness(endianness);
+        self.iface.write_accel_register(reg4).await?;
+        self.ctrl_reg4_a = reg4;
+        Ok(())
+    }
+
+    /// Get the selected accelerometer output data endianness.
+    ///
+    /// This is the selection, which only takes effect in high-resolution mode.
+    pub async fn get_accel_endianness(&self) -> Endianness {
+        self.ctrl_reg4_a.endianness()
+    }
 }
 
 fn check_accel_odr_is_compatible_with_mode<CommE>(
@@ -0,0 +1,122 @@
+use maybe_async_cfg::maybe;
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::{i2c, spi};
+#[cfg(feature = "async")]
+use embedded_hal_async::{i2c, spi};
+
+use crate::{
+    interface::{I2cInterface, ReadData, SpiInterface, WriteData},
+    mode,
+    register_address::{
+        CfgRegAM, CfgRegBM, CfgRegCM, CtrlReg1A, CtrlReg2A, CtrlReg3A, CtrlReg4A, CtrlReg5A,
+        CtrlReg6A, FifoCtrlRegA, IntCtrlRegM, TempCfgRegA,
+    },
+    Error, Lsm303agr, MagModeDevice, ModeChangeError,
+};
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<I2C, E> Lsm303agr<I2cInterface<I2C>, mode::MagOneShot>
+where
+    I2C: i2c::I2c<Error = E>,
+{
+    /// Create new instance of the LSM303AGR device communicating through I2C
+    /// and read its current configuration.
+    ///
+    /// See [`attach()`](Lsm303agr::attach).
+    pub async fn attach_with_i2c(
+        i2c: I2C,
+    ) -> Result<MagModeDevice<I2cInterface<I2C>>, ModeChangeError<E, Self>> {
+        Self::new_with_i2c(i2c).attach().await
+    }
+}
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<SPIXL, SPIMAG, CommE> Lsm303agr<SpiInterface<SPIXL, SPIMAG>, mode::MagOneShot>
+where
+    SPIXL: spi::SpiDevice<u8, Error = CommE>,
+    SPIMAG: spi::SpiDevice<u8, Error = CommE>,
+{
+    /// Create new instance of the LSM303AGR device communicating through SPI
+    /// and read its current configuration.
+    ///
+    /// See [`attach()`](Lsm303agr::attach).
+    pub async fn attach_with_spi(
+        spi_accel: SPIXL,
+        spi_mag: SPIMAG,
+    ) -> Result<MagModeDevice<SpiInterface<SPIXL, SPIMAG>>, ModeChangeError<CommE, Self>> {
+        Self::new_with_spi(spi_accel, spi_mag).attach().await
+    }
+}
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE> Lsm303agr<DI, mode::MagOneShot>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    /// Read the current configuration from the device.
+    ///
+    /// This is useful when the device has already been configured, for example
+    /// by a bootloader, and the driver must not assume the power-on defaults.
+    ///
+    /// The magnetometer mode is returned according to the configured mode:
+    /// continuous mode or one-shot mode otherwise.
+    pub async fn attach(mut self) -> Result<MagModeDevice<DI>, ModeChangeError<CommE, Self>> {
+        if let Err(error) = self.read_cached_registers().await {
+            return Err(ModeChangeError { error, dev: self });
+        }
+
+        if self.cfg_reg_a_m.is_continuous_mode() {
+            Ok(MagModeDevice::Continuous(self.with_mag_mode()))
+        } else {
+            Ok(MagModeDevice::OneShot(self))
+        }
+    }
+}
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE, MODE> Lsm303agr<DI, MODE>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    pub(crate) async fn read_cached_registers(&mut self) -> Result<(), Error<CommE>> {
+        self.ctrl_reg1_a = self.iface.read_accel_register::<CtrlReg1A>().await?;
+        self.ctrl_reg2_a = self.iface.read_accel_register::<CtrlReg2A>().await?;
+        self.ctrl_reg3_a = self.iface.read_accel_register::<CtrlReg3A>().await?;
+        self.ctrl_reg4_a = self.iface.read_accel_register::<CtrlReg4A>().await?;
+        // The BOOT bit is cleared automatically after booting.
+        self.ctrl_reg5_a = self
+            .iface
+            .read_accel_register::<CtrlReg5A>()
+            .await?
+            .difference(CtrlReg5A::BOOT);
+        self.ctrl_reg6_a = self.iface.read_accel_register::<CtrlReg6A>().await?;
+        self.temp_cfg_reg_a = self.iface.read_accel_register::<TempCfgRegA>().await?;
+        self.fifo_ctrl_reg_a = self.iface.read_accel_register::<FifoCtrlRegA>().await?;
+        self.accel_odr = self.ctrl_reg1_a.odr();
+
+        // The REBOOT and SOFT_RST bits are cleared automatically.
+        self.cfg_reg_a_m = self
+            .iface
+            .read_mag_register::<CfgRegAM>()
+            .await?
+            .difference(CfgRegAM::REBOOT.union(CfgRegAM::SOFT_RST));
+        self.cfg_reg_b_m = self.iface.read_mag_register::<CfgRegBM>().await?;
+        self.cfg_reg_c_m = self.iface.read_mag_register::<CfgRegCM>().await?;
+        self.int_ctrl_reg_m = self.iface.read_mag_register::<IntCtrlRegM>().await?;
+
+        Ok(())
+    }
+}
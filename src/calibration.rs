@@ -0,0 +1,608 @@
+//! Sensor calibration.
+//!
+//! ## Magnetometer
+//!
+//! The magnetometer calibration corrects hard-iron and soft-iron distortions
+//! using the minimum and maximum values measured on each axis while rotating
+//! the device in all directions. This only needs fixed memory, independent of
+//! the number of samples.
+//!
+//! ```no_run
+//! # #[cfg(target_os = "linux")] {
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use lsm303agr::{calibration::MagCalibrator, Lsm303agr, MagMode, MagOutputDataRate};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Lsm303agr::new_with_i2c(dev);
+//! sensor.init().unwrap();
+//! sensor.set_mag_mode_and_odr(&mut Delay, MagMode::HighResolution, MagOutputDataRate::Hz10).unwrap();
+//! let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+//!
+//! // Rotate the device in all directions while collecting samples.
+//! let mut calibrator = MagCalibrator::new();
+//! while calibrator.sample_count() < 1000 {
+//!     if sensor.mag_status().unwrap().xyz_new_data() {
+//!         calibrator.add_sample(&sensor.magnetic_field().unwrap());
+//!     }
+//! }
+//! let calibration = calibrator.calibration().unwrap();
+//!
+//! let field = calibration.apply(&sensor.magnetic_field().unwrap());
+//! println!("Magnetic field: x {} y {} z {}", field.x_nt(), field.y_nt(), field.z_nt());
+//! # }
+//! ```
+//!
+//! ## Accelerometer
+//!
+//! The accelerometer calibration corrects the offset and gain of each axis
+//! using measurements taken with the device resting in six positions, where each
+//! axis points up and down once.
+//!
+//! ```no_run
+//! # #[cfg(target_os = "linux")] {
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use lsm303agr::{calibration::AccelCalibrator, AccelMode, AccelOutputDataRate, Lsm303agr};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Lsm303agr::new_with_i2c(dev);
+//! sensor.init().unwrap();
+//! sensor.set_accel_mode_and_odr(&mut Delay, AccelMode::HighResolution, AccelOutputDataRate::Hz50).unwrap();
+//!
+//! let mut calibrator = AccelCalibrator::new(50);
+//! while let Some(position) = calibrator.next_position() {
+//!     println!("Place the device with {position:?} and keep it still");
+//!     while calibrator.next_position() == Some(position) {
+//!         if sensor.accel_status().unwrap().xyz_new_data() {
+//!             calibrator.add_sample(&sensor.acceleration().unwrap());
+//!         }
+//!     }
+//! }
+//! let calibration = calibrator.calibration().unwrap();
+//!
+//! let (x, y, z) = sensor.acceleration_calibrated(&calibration).unwrap();
+//! println!("Acceleration: x {x} y {y} z {z}");
+//! # }
+//! ```
+
+use crate::{Acceleration, MagneticField};
+
+/// Standard gravity in m*g* (milli-*g*).
+const GRAVITY_MG: f32 = 1000.0;
+
+/// Magnetometer calibration.
+///
+/// See [`MagCalibrator`] for how to obtain it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MagCalibration {
+    offset: (i16, i16, i16),
+    scale: (f32, f32, f32),
+}
+
+impl MagCalibration {
+    /// Create a calibration from a hard-iron offset in raw LSB and
+    /// per-axis soft-iron scale factors.
+    pub const fn new(
+        hard_iron_offset_raw: (i16, i16, i16),
+        soft_iron_scale: (f32, f32, f32),
+    ) -> Self {
+        Self {
+            offset: hard_iron_offset_raw,
+            scale: soft_iron_scale,
+        }
+    }
+
+    /// Hard-iron offset in raw LSB.
+    #[inline]
+    pub const fn hard_iron_offset_raw(&self) -> (i16, i16, i16) {
+        self.offset
+    }
+
+    /// Hard-iron offset in nT (nano-Tesla).
+    #[inline]
+    pub const fn hard_iron_offset_nt(&self) -> (i32, i32, i32) {
+        let (x, y, z) = self.offset;
+        (
+            x as i32 * MagneticField::SCALING_FACTOR,
+            y as i32 * MagneticField::SCALING_FACTOR,
+            z as i32 * MagneticField::SCALING_FACTOR,
+        )
+    }
+
+    /// Soft-iron scale factors.
+    #[inline]
+    pub const fn soft_iron_scale(&self) -> (f32, f32, f32) {
+        self.scale
+    }
+
+    /// Correct the hard-iron and soft-iron distortions of a measurement.
+    pub fn apply(&self, field: &MagneticField) -> MagneticField {
+        let (x, y, z) = field.xyz_unscaled();
+        let (offset_x, offset_y, offset_z) = self.offset;
+        self.scale(
+            x as i32 - offset_x as i32,
+            y as i32 - offset_y as i32,
+            z as i32 - offset_z as i32,
+        )
+    }
+
+    /// Correct only the soft-iron distortion of a measurement.
+    ///
+    /// Use this if the hard-iron offset has been written to the sensor with
+    /// [`set_mag_calibration()`](crate::Lsm303agr::set_mag_calibration).
+    pub fn apply_soft_iron(&self, field: &MagneticField) -> MagneticField {
+        let (x, y, z) = field.xyz_unscaled();
+        self.scale(x as i32, y as i32, z as i32)
+    }
+
+    fn scale(&self, x: i32, y: i32, z: i32) -> MagneticField {
+        let (scale_x, scale_y, scale_z) = self.scale;
+        MagneticField {
+            x: scale_raw(x, scale_x) as u16,
+            y: scale_raw(y, scale_y) as u16,
+            z: scale_raw(z, scale_z) as u16,
+        }
+    }
+}
+
+/// Scale a raw value, rounding to the nearest value and saturating.
+fn scale_raw(value: i32, scale: f32) -> i16 {
+    // Float to integer conversion saturates.
+    round(value as f32 * scale) as i16
+}
+
+/// Round half away from zero, to be converted into an integer.
+fn round(value: f32) -> f32 {
+    if value < 0.0 {
+        value - 0.5
+    } else {
+        value + 0.5
+    }
+}
+
+/// Magnetometer calibration from the minimum and maximum measured values.
+///
+/// The hard-iron offset is the center of the measured range on each axis.
+/// The soft-iron scale factors equalize the measured range of the axes.
+/// This corrects soft-iron distortions aligned with the sensor axes.
+///
+/// The hard-iron offset registers of the sensor must be cleared while
+/// collecting samples. See: [`set_mag_hard_iron_offset_raw()`](crate::Lsm303agr::set_mag_hard_iron_offset_raw).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MagCalibrator {
+    min: (i16, i16, i16),
+    max: (i16, i16, i16),
+    samples: u32,
+}
+
+impl Default for MagCalibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MagCalibrator {
+    /// Create a calibrator without samples.
+    pub const fn new() -> Self {
+        Self {
+            min: (i16::MAX, i16::MAX, i16::MAX),
+            max: (i16::MIN, i16::MIN, i16::MIN),
+            samples: 0,
+        }
+    }
+
+    /// Add a magnetic field measurement.
+    pub fn add_sample(&mut self, field: &MagneticField) {
+        let (x, y, z) = field.xyz_unscaled();
+        self.min = (self.min.0.min(x), self.min.1.min(y), self.min.2.min(z));
+        self.max = (self.max.0.max(x), self.max.1.max(y), self.max.2.max(z));
+        self.samples = self.samples.saturating_add(1);
+    }
+
+    /// Number of added samples.
+    #[inline]
+    pub const fn sample_count(&self) -> u32 {
+        self.samples
+    }
+
+    /// Compute the calibration.
+    ///
+    /// Returns `None` if the measured range of any axis is empty.
+    pub fn calibration(&self) -> Option<MagCalibration> {
+        let (min, max) = (self.min, self.max);
+        let offset = |min: i16, max: i16| ((min as i32 + max as i32) / 2) as i16;
+        let radius = |min: i16, max: i16| (max as i32 - min as i32) as f32 / 2.0;
+        let (rx, ry, rz) = (
+            radius(min.0, max.0),
+            radius(min.1, max.1),
+            radius(min.2, max.2),
+        );
+        if rx <= 0.0 || ry <= 0.0 || rz <= 0.0 {
+            return None;
+        }
+
+        let average = (rx + ry + rz) / 3.0;
+        Some(MagCalibration::new(
+            (
+                offset(min.0, max.0),
+                offset(min.1, max.1),
+                offset(min.2, max.2),
+            ),
+            (average / rx, average / ry, average / rz),
+        ))
+    }
+}
+
+/// Accelerometer calibration.
+///
+/// See [`AccelCalibrator`] for how to obtain it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccelCalibration {
+    bias: (i32, i32, i32),
+    scale: (f32, f32, f32),
+}
+
+impl AccelCalibration {
+    /// Create a calibration from a per-axis bias in m*g* (milli-*g*) and
+    /// per-axis scale factors.
+    pub const fn new(bias_mg: (i32, i32, i32), scale: (f32, f32, f32)) -> Self {
+        Self {
+            bias: bias_mg,
+            scale,
+        }
+    }
+
+    /// Bias (zero-*g* offset) in m*g* (milli-*g*).
+    #[inline]
+    pub const fn bias_mg(&self) -> (i32, i32, i32) {
+        self.bias
+    }
+
+    /// Scale factors.
+    #[inline]
+    pub const fn scale(&self) -> (f32, f32, f32) {
+        self.scale
+    }
+
+    /// Correct the offset and gain of a measurement.
+    ///
+    /// Returns the acceleration in X-, Y- and Z-directions in m*g* (milli-*g*).
+    pub fn apply(&self, acceleration: &Acceleration) -> (i32, i32, i32) {
+        let (x, y, z) = acceleration.xyz_mg();
+        let (bias_x, bias_y, bias_z) = self.bias;
+        let (scale_x, scale_y, scale_z) = self.scale;
+        (
+            round((x - bias_x) as f32 * scale_x) as i32,
+            round((y - bias_y) as f32 * scale_y) as i32,
+            round((z - bias_z) as f32 * scale_z) as i32,
+        )
+    }
+}
+
+/// Resting position of the device during accelerometer calibration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccelPosition {
+    /// X axis pointing up
+    XUp,
+    /// X axis pointing down
+    XDown,
+    /// Y axis pointing up
+    YUp,
+    /// Y axis pointing down
+    YDown,
+    /// Z axis pointing up (device lying flat)
+    ZUp,
+    /// Z axis pointing down (device lying upside down)
+    ZDown,
+}
+
+impl AccelPosition {
+    const ALL: [Self; 6] = [
+        Self::XUp,
+        Self::XDown,
+        Self::YUp,
+        Self::YDown,
+        Self::ZUp,
+        Self::ZDown,
+    ];
+
+    /// Check that the axis of this position measures most of the gravity
+    /// in the expected direction.
+    fn matches(self, (x, y, z): (i32, i32, i32)) -> bool {
+        let (value, others) = match self {
+            Self::XUp | Self::XDown => (x, [y, z]),
+            Self::YUp | Self::YDown => (y, [x, z]),
+            Self::ZUp | Self::ZDown => (z, [x, y]),
+        };
+        let up = matches!(self, Self::XUp | Self::YUp | Self::ZUp);
+        (value > 0) == up && others.iter().all(|other| other.abs() < value.abs())
+    }
+}
+
+/// Guided 6-position accelerometer calibration.
+///
+/// The device must rest in each of the positions returned by
+/// [`next_position()`](AccelCalibrator::next_position) while samples
+/// are added. The measurements of each position are averaged.
+///
+/// The bias of each axis is the middle point between the measurements with the
+/// axis pointing up and down. The scale factor maps these measurements to ±1 *g*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccelCalibrator {
+    samples_per_position: u32,
+    sums: [i64; 6],
+    counts: [u32; 6],
+}
+
+impl AccelCalibrator {
+    /// Create a calibrator taking `samples_per_position` samples in each position.
+    ///
+    /// At least one sample is taken in each position.
+    pub const fn new(samples_per_position: u32) -> Self {
+        Self {
+            samples_per_position: if samples_per_position == 0 {
+                1
+            } else {
+                samples_per_position
+            },
+            sums: [0; 6],
+            counts: [0; 6],
+        }
+    }
+
+    /// Position the device must rest in for the next samples.
+    ///
+    /// Returns `None` once all positions have been measured.
+    pub fn next_position(&self) -> Option<AccelPosition> {
+        AccelPosition::ALL
+            .into_iter()
+            .zip(self.counts)
+            .find(|(_, count)| *count < self.samples_per_position)
+            .map(|(position, _)| position)
+    }
+
+    /// Add a measurement for the position returned by
+    /// [`next_position()`](AccelCalibrator::next_position).
+    ///
+    /// Returns `false` and ignores the measurement if the device does not seem
+    /// to be in that position or all positions have been measured.
+    pub fn add_sample(&mut self, acceleration: &Acceleration) -> bool {
+        let position = match self.next_position() {
+            Some(position) => position,
+            None => return false,
+        };
+        let xyz = acceleration.xyz_mg();
+        if !position.matches(xyz) {
+            return false;
+        }
+
+        let value = match position {
+            AccelPosition::XUp | AccelPosition::XDown => xyz.0,
+            AccelPosition::YUp | AccelPosition::YDown => xyz.1,
+            AccelPosition::ZUp | AccelPosition::ZDown => xyz.2,
+        };
+        let index = position as usize;
+        self.sums[index] += i64::from(value);
+        self.counts[index] += 1;
+        true
+    }
+
+    /// Compute the calibration.
+    ///
+    /// Returns `None` if not all positions have been measured.
+    pub fn calibration(&self) -> Option<AccelCalibration> {
+        if self.next_position().is_some() {
+            return None;
+        }
+
+        let average = |index: usize| self.sums[index] as f32 / self.counts[index] as f32;
+        // The position checks ensure the up average is positive and the down average negative.
+        let axis = |up: AccelPosition, down: AccelPosition| {
+            let (up, down) = (average(up as usize), average(down as usize));
+            (
+                round((up + down) / 2.0) as i32,
+                2.0 * GRAVITY_MG / (up - down),
+            )
+        };
+        let (bias_x, scale_x) = axis(AccelPosition::XUp, AccelPosition::XDown);
+        let (bias_y, scale_y) = axis(AccelPosition::YUp, AccelPosition::YDown);
+        let (bias_z, scale_z) = axis(AccelPosition::ZUp, AccelPosition::ZDown);
+        Some(AccelCalibration::new(
+            (bias_x, bias_y, bias_z),
+            (scale_x, scale_y, scale_z),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{AccelCalibration, AccelCalibrator, AccelPosition, MagCalibration, MagCalibrator};
+    use crate::{AccelMode, AccelScale, Acceleration, AxisSet, MagneticField};
+    use std::vec::Vec;
+
+    const EARTH_FIELD_NT: (f32, f32, f32) = (20_000.0, 0.0, -40_000.0);
+    const HARD_IRON_NT: (f32, f32, f32) = (15_000.0, -9_000.0, 3_000.0);
+    const SOFT_IRON: (f32, f32, f32) = (1.2, 0.9, 1.05);
+
+    fn field(x: f32, y: f32, z: f32) -> MagneticField {
+        let raw = |nt: f32| (nt / MagneticField::SCALING_FACTOR as f32).round() as i16 as u16;
+        MagneticField {
+            x: raw(x),
+            y: raw(y),
+            z: raw(z),
+        }
+    }
+
+    /// Earth magnetic field measured by a distorted sensor rotated in all directions.
+    fn rotated_samples() -> Vec<MagneticField> {
+        let (ex, ey, ez) = EARTH_FIELD_NT;
+        let mut samples = Vec::new();
+        for yaw in (0..360).step_by(10) {
+            for pitch in (0..360).step_by(10) {
+                let (sin_yaw, cos_yaw) = (yaw as f32).to_radians().sin_cos();
+                let (sin_pitch, cos_pitch) = (pitch as f32).to_radians().sin_cos();
+                // Rotation around Y, then around Z.
+                let (x, z) = (
+                    ex * cos_pitch + ez * sin_pitch,
+                    -ex * sin_pitch + ez * cos_pitch,
+                );
+                let (x, y) = (x * cos_yaw - ey * sin_yaw, x * sin_yaw + ey * cos_yaw);
+                samples.push(field(
+                    x * SOFT_IRON.0 + HARD_IRON_NT.0,
+                    y * SOFT_IRON.1 + HARD_IRON_NT.1,
+                    z * SOFT_IRON.2 + HARD_IRON_NT.2,
+                ));
+            }
+        }
+        samples
+    }
+
+    fn magnitude(field: &MagneticField) -> f32 {
+        let (x, y, z) = field.xyz_nt();
+        let (x, y, z) = (x as f32, y as f32, z as f32);
+        (x * x + y * y + z * z).sqrt()
+    }
+
+    fn calibrate() -> (MagCalibration, Vec<MagneticField>) {
+        let samples = rotated_samples();
+        let mut calibrator = MagCalibrator::new();
+        for sample in &samples {
+            calibrator.add_sample(sample);
+        }
+        assert_eq!(calibrator.sample_count(), samples.len() as u32);
+        (calibrator.calibration().unwrap(), samples)
+    }
+
+    #[test]
+    fn no_calibration_without_samples() {
+        assert_eq!(MagCalibrator::new().calibration(), None);
+        let mut calibrator = MagCalibrator::default();
+        calibrator.add_sample(&field(1000.0, 2000.0, 3000.0));
+        assert_eq!(calibrator.calibration(), None);
+    }
+
+    #[test]
+    fn finds_hard_iron_offset() {
+        let (calibration, _) = calibrate();
+        let (x, y, z) = calibration.hard_iron_offset_nt();
+        assert!((x as f32 - HARD_IRON_NT.0).abs() <= 300.0);
+        assert!((y as f32 - HARD_IRON_NT.1).abs() <= 300.0);
+        assert!((z as f32 - HARD_IRON_NT.2).abs() <= 300.0);
+    }
+
+    #[test]
+    fn finds_soft_iron_scale() {
+        let (calibration, _) = calibrate();
+        let (x, y, z) = calibration.soft_iron_scale();
+        assert!((x * SOFT_IRON.0 - y * SOFT_IRON.1).abs() < 0.01);
+        assert!((x * SOFT_IRON.0 - z * SOFT_IRON.2).abs() < 0.01);
+    }
+
+    #[test]
+    fn corrected_samples_lie_on_a_sphere() {
+        let (calibration, samples) = calibrate();
+        let magnitudes: Vec<f32> = samples
+            .iter()
+            .map(|s| magnitude(&calibration.apply(s)))
+            .collect();
+        let average = magnitudes.iter().sum::<f32>() / magnitudes.len() as f32;
+        for m in magnitudes {
+            assert!((m - average).abs() / average < 0.02, "{m} vs {average}");
+        }
+    }
+
+    #[test]
+    fn can_apply_soft_iron_only() {
+        let calibration = MagCalibration::new((100, 200, 300), (2.0, 0.5, -1.0));
+        let corrected = calibration.apply_soft_iron(&field(1500.0, -1500.0, 1500.0));
+        assert_eq!(corrected.xyz_unscaled(), (20, -5, -10));
+        let corrected = calibration.apply(&field(16500.0, 31500.0, 46500.0));
+        assert_eq!(corrected.xyz_unscaled(), (20, 5, -10));
+    }
+
+    /// Measurement of a sensor with bias (40, -60, 25) mg and gains (1.02, 0.97, 1.01).
+    fn biased_acceleration(x_g: i32, y_g: i32, z_g: i32) -> Acceleration {
+        let raw = |g: i32, bias: f32, gain: f32| ((g as f32 * 1000.0 * gain + bias) as i16) << 4;
+        // High resolution mode with ±2g scale: 1 mg/LSB, left-justified.
+        Acceleration {
+            x: raw(x_g, 40.0, 1.02) as u16,
+            y: raw(y_g, -60.0, 0.97) as u16,
+            z: raw(z_g, 25.0, 1.01) as u16,
+            mode: AccelMode::HighResolution,
+            scale: AccelScale::G2,
+            axes: AxisSet::all(),
+        }
+    }
+
+    fn resting(position: AccelPosition) -> Acceleration {
+        match position {
+            AccelPosition::XUp => biased_acceleration(1, 0, 0),
+            AccelPosition::XDown => biased_acceleration(-1, 0, 0),
+            AccelPosition::YUp => biased_acceleration(0, 1, 0),
+            AccelPosition::YDown => biased_acceleration(0, -1, 0),
+            AccelPosition::ZUp => biased_acceleration(0, 0, 1),
+            AccelPosition::ZDown => biased_acceleration(0, 0, -1),
+        }
+    }
+
+    #[test]
+    fn accel_positions_are_guided() {
+        let mut calibrator = AccelCalibrator::new(2);
+        assert_eq!(calibrator.calibration(), None);
+        for position in [
+            AccelPosition::XUp,
+            AccelPosition::XDown,
+            AccelPosition::YUp,
+            AccelPosition::YDown,
+            AccelPosition::ZUp,
+        ] {
+            assert_eq!(calibrator.next_position(), Some(position));
+            assert!(calibrator.add_sample(&resting(position)));
+            assert!(calibrator.add_sample(&resting(position)));
+        }
+        assert_eq!(calibrator.next_position(), Some(AccelPosition::ZDown));
+        // Wrong position
+        assert!(!calibrator.add_sample(&resting(AccelPosition::ZUp)));
+        assert_eq!(calibrator.calibration(), None);
+        assert!(calibrator.add_sample(&resting(AccelPosition::ZDown)));
+        assert!(calibrator.add_sample(&resting(AccelPosition::ZDown)));
+        assert_eq!(calibrator.next_position(), None);
+        assert!(!calibrator.add_sample(&resting(AccelPosition::ZDown)));
+        assert!(calibrator.calibration().is_some());
+    }
+
+    #[test]
+    fn finds_accel_bias_and_scale() {
+        let mut calibrator = AccelCalibrator::new(1);
+        while let Some(position) = calibrator.next_position() {
+            assert!(calibrator.add_sample(&resting(position)));
+        }
+        let calibration = calibrator.calibration().unwrap();
+        assert_eq!(calibration.bias_mg(), (40, -60, 25));
+        let (x, y, z) = calibration.scale();
+        assert!((x - 1.0 / 1.02).abs() < 0.001);
+        assert!((y - 1.0 / 0.97).abs() < 0.001);
+        assert!((z - 1.0 / 1.01).abs() < 0.001);
+
+        assert_eq!(calibration.apply(&biased_acceleration(0, 0, 0)), (0, 0, 0));
+        assert_eq!(
+            calibration.apply(&biased_acceleration(1, -1, 1)),
+            (1000, -1000, 1000)
+        );
+    }
+
+    #[test]
+    fn can_apply_accel_calibration() {
+        let calibration = AccelCalibration::new((10, -10, 0), (2.0, 0.5, 1.0));
+        let acceleration = Acceleration {
+            x: 110 << 4,
+            y: 90 << 4,
+            z: (-5i16 << 4) as u16,
+            mode: AccelMode::HighResolution,
+            scale: AccelScale::G2,
+            axes: AxisSet::all(),
+        };
+        assert_eq!(calibration.apply(&acceleration), (200, 50, -5));
+    }
+}
@@ -0,0 +1,193 @@
+//! Tilt-compensated eCompass.
+//!
+//! This module is only available if the `libm` feature is enabled.
+//!
+//! The heading is computed following the application note [AN4990] (section 5.2).
+//! The accelerometer is used to measure the pitch and roll of the device, which are
+//! then used to project the magnetic field onto the horizontal plane.
+//!
+//! The accelerometer and magnetometer axes of the LSM303AGR are aligned, so the
+//! measurements can be used as returned by the driver. The heading is measured
+//! clockwise from the X axis of the device.
+//!
+//! [AN4990]: https://www.st.com/resource/en/application_note/dm00265383-ultracompact-highperformance-ecompass-module-based-on-the-lsm303agr-stmicroelectronics.pdf
+//!
+//! ```no_run
+//! # #[cfg(target_os = "linux")] {
+//! use linux_embedded_hal::{Delay, I2cdev};
+//! use lsm303agr::{
+//!     compass, AccelMode, AccelOutputDataRate, Lsm303agr, MagMode, MagOutputDataRate,
+//! };
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Lsm303agr::new_with_i2c(dev);
+//! sensor.init().unwrap();
+//! sensor.set_accel_mode_and_odr(&mut Delay, AccelMode::HighResolution, AccelOutputDataRate::Hz50).unwrap();
+//! sensor.set_mag_mode_and_odr(&mut Delay, MagMode::HighResolution, MagOutputDataRate::Hz10).unwrap();
+//! let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+//!
+//! let acceleration = sensor.acceleration().unwrap();
+//! let magnetic_field = sensor.magnetic_field().unwrap();
+//! // Magnetic declination of +1.5° (east).
+//! let orientation = compass::orientation(&acceleration, &magnetic_field, 1.5);
+//! println!("Heading: {}°", orientation.heading());
+//! # }
+//! ```
+
+use libm::{asinf, atan2f, cosf, fmodf, sinf, sqrtf};
+
+use crate::{Acceleration, MagneticField};
+
+/// Device orientation.
+///
+/// All angles are in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orientation {
+    heading: f32,
+    pitch: f32,
+    roll: f32,
+}
+
+impl Orientation {
+    /// Heading in the range `[0, 360)`.
+    ///
+    /// This is relative to true north if the magnetic declination was given,
+    /// otherwise it is relative to magnetic north.
+    #[inline]
+    pub const fn heading(&self) -> f32 {
+        self.heading
+    }
+
+    /// Pitch in the range `[-90, 90]`. Positive when the X axis points up.
+    #[inline]
+    pub const fn pitch(&self) -> f32 {
+        self.pitch
+    }
+
+    /// Roll in the range `(-180, 180]`. Positive when the Y axis points up.
+    #[inline]
+    pub const fn roll(&self) -> f32 {
+        self.roll
+    }
+}
+
+/// Compute the tilt-compensated heading, pitch and roll of the device.
+///
+/// `declination` is the magnetic declination at the current location in degrees,
+/// positive when magnetic north is east of true north. Use `0.0` to get the
+/// heading relative to magnetic north.
+///
+/// The acceleration must only contain gravity for the result to be accurate,
+/// i.e. the device must not be accelerating. The magnetic field should be
+/// calibrated for hard-iron and soft-iron distortions.
+pub fn orientation(
+    acceleration: &Acceleration,
+    magnetic_field: &MagneticField,
+    declination: f32,
+) -> Orientation {
+    let (ax, ay, az) = acceleration.xyz_mg();
+    let (ax, ay, az) = (ax as f32, ay as f32, az as f32);
+    let norm = sqrtf(ax * ax + ay * ay + az * az);
+    // Clamp to avoid NaN due to rounding.
+    let pitch = asinf((ax / norm).clamp(-1.0, 1.0));
+    let roll = atan2f(ay, az);
+
+    let (mx, my, mz) = magnetic_field.xyz_nt();
+    let (mx, my, mz) = (mx as f32, my as f32, mz as f32);
+    let (sin_pitch, cos_pitch) = (sinf(pitch), cosf(pitch));
+    let (sin_roll, cos_roll) = (sinf(roll), cosf(roll));
+    let xh = mx * cos_pitch - (my * sin_roll + mz * cos_roll) * sin_pitch;
+    let yh = my * cos_roll - mz * sin_roll;
+
+    let mut heading = fmodf(atan2f(yh, xh).to_degrees() + declination, 360.0);
+    if heading < 0.0 {
+        heading += 360.0;
+    }
+    Orientation {
+        // Adding 360.0 to a tiny negative value rounds to 360.0.
+        heading: if heading >= 360.0 { 0.0 } else { heading },
+        pitch: pitch.to_degrees(),
+        roll: roll.to_degrees(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::orientation;
+    use crate::{AccelMode, AccelScale, Acceleration, AxisSet, MagneticField};
+
+    fn acceleration(x_mg: i16, y_mg: i16, z_mg: i16) -> Acceleration {
+        // High resolution mode with ±2g scale: 1 mg/LSB, left-justified.
+        Acceleration {
+            x: (x_mg << 4) as u16,
+            y: (y_mg << 4) as u16,
+            z: (z_mg << 4) as u16,
+            mode: AccelMode::HighResolution,
+            scale: AccelScale::G2,
+            axes: AxisSet::all(),
+        }
+    }
+
+    fn magnetic_field(x: i16, y: i16, z: i16) -> MagneticField {
+        MagneticField {
+            x: x as u16,
+            y: y as u16,
+            z: z as u16,
+        }
+    }
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 0.5, "{value} != {expected}");
+    }
+
+    #[test]
+    fn flat() {
+        let flat = acceleration(0, 0, 1000);
+        for (x, y, heading) in [
+            (100, 0, 0.0),
+            (0, 100, 90.0),
+            (-100, 0, 180.0),
+            (0, -100, 270.0),
+        ] {
+            let o = orientation(&flat, &magnetic_field(x, y, -200), 0.0);
+            assert_close(o.heading(), heading);
+            assert_close(o.pitch(), 0.0);
+            assert_close(o.roll(), 0.0);
+        }
+    }
+
+    #[test]
+    fn declination_wraps_around() {
+        let flat = acceleration(0, 0, 1000);
+        let north = magnetic_field(100, 0, -200);
+        assert_close(orientation(&flat, &north, 10.0).heading(), 10.0);
+        assert_close(orientation(&flat, &north, -10.0).heading(), 350.0);
+    }
+
+    // The following use a field with a 60° inclination, i.e. 0.5 pointing
+    // north and -0.866 pointing up, rotated into the device frame.
+
+    #[test]
+    fn pitched_up_facing_east() {
+        let o = orientation(
+            &acceleration(500, 0, 866),
+            &magnetic_field(-433, 500, -750),
+            0.0,
+        );
+        assert_close(o.pitch(), 30.0);
+        assert_close(o.roll(), 0.0);
+        assert_close(o.heading(), 90.0);
+    }
+
+    #[test]
+    fn rolled_facing_east() {
+        let o = orientation(
+            &acceleration(0, 500, 866),
+            &magnetic_field(0, 0, -1000),
+            0.0,
+        );
+        assert_close(o.pitch(), 0.0);
+        assert_close(o.roll(), 30.0);
+        assert_close(o.heading(), 90.0);
+    }
+}
@@ -0,0 +1,118 @@
+use maybe_async_cfg::maybe;
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::delay::DelayNs;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs;
+
+use crate::{
+    interface::{ReadData, WriteData},
+    register_address::{CfgRegAM, CtrlReg5A},
+    DeviceConfig, Error, Lsm303agr,
+};
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE, MODE> Lsm303agr<DI, MODE>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    /// Get the current device configuration.
+    ///
+    /// This is the configuration cached in the driver.
+    pub fn config(&self) -> DeviceConfig {
+        DeviceConfig {
+            ctrl_reg1_a: self.ctrl_reg1_a,
+            ctrl_reg2_a: self.ctrl_reg2_a,
+            ctrl_reg3_a: self.ctrl_reg3_a,
+            ctrl_reg4_a: self.ctrl_reg4_a,
+            ctrl_reg5_a: self.ctrl_reg5_a,
+            ctrl_reg6_a: self.ctrl_reg6_a,
+            temp_cfg_reg_a: self.temp_cfg_reg_a,
+            fifo_ctrl_reg_a: self.fifo_ctrl_reg_a,
+            cfg_reg_a_m: self.cfg_reg_a_m,
+            cfg_reg_b_m: self.cfg_reg_b_m,
+            cfg_reg_c_m: self.cfg_reg_c_m,
+            int_ctrl_reg_m: self.int_ctrl_reg_m,
+        }
+    }
+
+    /// Apply a device configuration.
+    ///
+    /// Only the registers contained in [`DeviceConfig`] are written.
+    ///
+    /// The accelerometer and magnetometer are configured before being turned on
+    /// and the given `delay` is used to wait for them to turn on or change modes.
+    ///
+    /// The magnetometer keeps its current one-shot or continuous mode.
+    /// Use [`into_mag_continuous()`](Lsm303agr::into_mag_continuous) or
+    /// [`into_mag_one_shot()`](Lsm303agr::into_mag_one_shot) to change it.
+    pub async fn apply_config<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        config: &DeviceConfig,
+    ) -> Result<(), Error<CommE>> {
+        let old_accel_mode = self.get_accel_mode().await;
+
+        self.iface.write_accel_register(config.ctrl_reg4_a).await?;
+        self.ctrl_reg4_a = config.ctrl_reg4_a;
+        self.iface.write_accel_register(config.ctrl_reg2_a).await?;
+        self.ctrl_reg2_a = config.ctrl_reg2_a;
+        self.iface.write_accel_register(config.ctrl_reg3_a).await?;
+        self.ctrl_reg3_a = config.ctrl_reg3_a;
+        let reg5 = config.ctrl_reg5_a.difference(CtrlReg5A::BOOT);
+        self.iface.write_accel_register(reg5).await?;
+        self.ctrl_reg5_a = reg5;
+        self.iface.write_accel_register(config.ctrl_reg6_a).await?;
+        self.ctrl_reg6_a = config.ctrl_reg6_a;
+        self.iface
+            .write_accel_register(config.fifo_ctrl_reg_a)
+            .await?;
+        self.fifo_ctrl_reg_a = config.fifo_ctrl_reg_a;
+        self.iface
+            .write_accel_register(config.temp_cfg_reg_a)
+            .await?;
+        self.temp_cfg_reg_a = config.temp_cfg_reg_a;
+        self.iface.write_accel_register(config.ctrl_reg1_a).await?;
+        self.ctrl_reg1_a = config.ctrl_reg1_a;
+        self.accel_odr = config.ctrl_reg1_a.odr();
+
+        if let Some(odr) = self.accel_odr {
+            let change_time = old_accel_mode.change_time_us(self.get_accel_mode().await, odr);
+            delay.delay_us(change_time).await;
+        }
+
+        let old_rega = self.cfg_reg_a_m;
+
+        self.iface.write_mag_register(config.cfg_reg_c_m).await?;
+        self.cfg_reg_c_m = config.cfg_reg_c_m;
+        self.iface.write_mag_register(config.cfg_reg_b_m).await?;
+        self.cfg_reg_b_m = config.cfg_reg_b_m;
+        self.iface.write_mag_register(config.int_ctrl_reg_m).await?;
+        self.int_ctrl_reg_m = config.int_ctrl_reg_m;
+
+        let rega = config
+            .cfg_reg_a_m
+            .difference(
+                CfgRegAM::MD
+                    .union(CfgRegAM::REBOOT)
+                    .union(CfgRegAM::SOFT_RST),
+            )
+            .union(old_rega.intersection(CfgRegAM::MD));
+        self.iface.write_mag_register(rega).await?;
+        self.cfg_reg_a_m = rega;
+
+        let offset_cancellation = self.cfg_reg_b_m.offset_cancellation();
+        if old_rega.mode() != rega.mode() {
+            delay
+                .delay_us(rega.turn_on_time_us(offset_cancellation))
+                .await;
+        } else if old_rega.odr() != rega.odr() && offset_cancellation {
+            delay.delay_us(rega.odr().turn_on_time_us_frac_1()).await;
+        }
+
+        Ok(())
+    }
+}
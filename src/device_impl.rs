@@ -1,14 +1,17 @@
 use maybe_async_cfg::maybe;
 
 use crate::{
+    calibration::AccelCalibration,
     interface::{I2cInterface, ReadData, SpiInterface, WriteData},
     mode,
     register_address::{
-        CfgRegAM, CfgRegBM, CfgRegCM, CtrlReg1A, CtrlReg3A, CtrlReg4A, CtrlReg5A, FifoCtrlRegA,
-        StatusRegA, StatusRegAuxA, StatusRegM, TempCfgRegA, WhoAmIA, WhoAmIM,
+        CfgRegAM, CfgRegBM, CfgRegCM, CtrlReg1A, CtrlReg2A, CtrlReg3A, CtrlReg4A, CtrlReg5A,
+        CtrlReg6A, FifoCtrlRegA, FifoSrcRegA, IntCtrlRegM, ReferenceA, RegRead, StatusRegA,
+        StatusRegAuxA, StatusRegM, TempCfgRegA, WhoAmIA, WhoAmIM,
     },
-    Acceleration, AccelerometerId, Error, FifoMode, Interrupt, Lsm303agr, MagnetometerId,
-    PhantomData, Status, Temperature, TemperatureStatus,
+    AccelHighPassConfig, Acceleration, AccelerometerId, Error, FifoMode, FifoStatus, Interrupt,
+    InterruptPin, InterruptPolarity, Lsm303agr, MagSetPulseFrequency, MagnetometerId, PhantomData,
+    Status, Temperature, TemperatureStatus,
 };
 
 impl<I2C> Lsm303agr<I2cInterface<I2C>, mode::MagOneShot> {
@@ -17,12 +20,15 @@ impl<I2C> Lsm303agr<I2cInterface<I2C>, mode::MagOneShot> {
         Lsm303agr {
             iface: I2cInterface { i2c },
             ctrl_reg1_a: CtrlReg1A::default(),
+            ctrl_reg2_a: CtrlReg2A::default(),
             ctrl_reg3_a: CtrlReg3A::default(),
             ctrl_reg4_a: CtrlReg4A::default(),
             ctrl_reg5_a: CtrlReg5A::default(),
+            ctrl_reg6_a: CtrlReg6A::default(),
             cfg_reg_a_m: CfgRegAM::default(),
             cfg_reg_b_m: CfgRegBM::default(),
             cfg_reg_c_m: CfgRegCM::default(),
+            int_ctrl_reg_m: IntCtrlRegM::default(),
             temp_cfg_reg_a: TempCfgRegA::default(),
             fifo_ctrl_reg_a: FifoCtrlRegA::default(),
             accel_odr: None,
@@ -45,20 +51,47 @@ impl<SPIXL, SPIMAG> Lsm303agr<SpiInterface<SPIXL, SPIMAG>, mode::MagOneShot> {
             iface: SpiInterface {
                 spi_xl: spi_accel,
                 spi_mag,
+                three_wire: false,
             },
             ctrl_reg1_a: CtrlReg1A::default(),
+            ctrl_reg2_a: CtrlReg2A::default(),
             ctrl_reg3_a: CtrlReg3A::default(),
             ctrl_reg4_a: CtrlReg4A::default(),
             ctrl_reg5_a: CtrlReg5A::default(),
+            ctrl_reg6_a: CtrlReg6A::default(),
             cfg_reg_a_m: CfgRegAM::default(),
             cfg_reg_b_m: CfgRegBM::default(),
             cfg_reg_c_m: CfgRegCM::default(),
+            int_ctrl_reg_m: IntCtrlRegM::default(),
             temp_cfg_reg_a: TempCfgRegA::default(),
             fifo_ctrl_reg_a: FifoCtrlRegA::default(),
             accel_odr: None,
             _mag_mode: PhantomData,
         }
     }
+
+    /// Create new instance of the LSM303AGR device communicating through 3-wire SPI.
+    ///
+    /// The accelerometer is switched into 3-wire SPI mode on [`init()`](Lsm303agr::init),
+    /// so this must be called before reading any data. The magnetometer uses 3-wire SPI
+    /// by default.
+    ///
+    /// The SPI devices must support half-duplex transactions on the shared data line.
+    pub fn new_with_spi_3wire(spi_accel: SPIXL, spi_mag: SPIMAG) -> Self {
+        let mut dev = Self::new_with_spi(spi_accel, spi_mag);
+        dev.iface.three_wire = true;
+        dev.ctrl_reg4_a = CtrlReg4A::SPI_ENABLE;
+        dev
+    }
+
+    /// Disable the magnetometer I2C interface on [`init()`](Lsm303agr::init).
+    ///
+    /// This prevents other I2C traffic on the shared pins from confusing the
+    /// magnetometer. See also [`set_mag_i2c_enabled()`](Lsm303agr::set_mag_i2c_enabled).
+    pub fn with_mag_i2c_disabled(mut self) -> Self {
+        self.cfg_reg_c_m = self.cfg_reg_c_m.union(CfgRegCM::I2C_DIS);
+        self
+    }
 }
 
 impl<SPIXL, SPIMAG, MODE> Lsm303agr<SpiInterface<SPIXL, SPIMAG>, MODE> {
@@ -77,9 +110,19 @@ where
     DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
 {
     /// Initialize registers
+    ///
+    /// This enables block data update for both sensors, the temperature sensor
+    /// and the magnetometer temperature compensation.
+    ///
+    /// When communicating through SPI, this also disables the magnetometer I2C
+    /// interface if requested with
+    /// [`with_mag_i2c_disabled()`](Lsm303agr::with_mag_i2c_disabled) and switches
+    /// the accelerometer into 3-wire SPI mode if created with
+    /// [`new_with_spi_3wire()`](Lsm303agr::new_with_spi_3wire).
     pub async fn init(&mut self) -> Result<(), Error<CommE>> {
         self.acc_enable_temp().await?; // Also enables BDU.
-        self.mag_enable_bdu().await
+        self.mag_enable_bdu().await?;
+        self.enable_mag_temperature_compensation().await
     }
 
     /// Enable block data update for accelerometer.
@@ -133,7 +176,50 @@ where
         Ok(())
     }
 
-    /// Enable accelerometer interrupt.
+    /// Get the accelerometer FIFO status.
+    pub async fn fifo_status(&mut self) -> Result<FifoStatus, Error<CommE>> {
+        self.iface
+            .read_accel_register::<FifoSrcRegA>()
+            .await
+            .map(FifoStatus::new)
+    }
+
+    /// Read the samples stored in the accelerometer FIFO.
+    ///
+    /// Reads up to `data.len()` samples (at most 32) in a single transaction and
+    /// returns the number of samples read. Each sample is tagged with the current
+    /// accelerometer mode and scale.
+    pub async fn read_fifo(&mut self, data: &mut [Acceleration]) -> Result<usize, Error<CommE>> {
+        let len = self.fifo_status().await?.len().min(data.len());
+        if len == 0 {
+            return Ok(0);
+        }
+
+        let mut buffer = [0; FifoStatus::DEPTH * 6];
+        let buffer = &mut buffer[..len * 6];
+        self.iface
+            .read_accel_registers(<Acceleration as RegRead<(u16, u16, u16)>>::ADDR, buffer)
+            .await?;
+
+        let mode = self.get_accel_mode().await;
+        let scale = self.get_accel_scale().await;
+        let axes = self.get_accel_axes().await;
+        let endianness = self.ctrl_reg4_a.output_endianness();
+        for (sample, bytes) in data.iter_mut().zip(buffer.chunks_exact(6)) {
+            *sample = Acceleration {
+                x: endianness.u16_from_bytes([bytes[0], bytes[1]]),
+                y: endianness.u16_from_bytes([bytes[2], bytes[3]]),
+                z: endianness.u16_from_bytes([bytes[4], bytes[5]]),
+                mode,
+                scale,
+                axes,
+            };
+        }
+
+        Ok(len)
+    }
+
+    /// Enable accelerometer interrupt on INT1 pin.
     pub async fn acc_enable_interrupt(&mut self, interrupt: Interrupt) -> Result<(), Error<CommE>> {
         let reg3 = self.ctrl_reg3_a.with_interrupt(interrupt);
         self.iface.write_accel_register(reg3).await?;
@@ -142,7 +228,7 @@ where
         Ok(())
     }
 
-    /// Disable accelerometer interrupt.
+    /// Disable accelerometer interrupt on INT1 pin.
     pub async fn acc_disable_interrupt(
         &mut self,
         interrupt: Interrupt,
@@ -154,6 +240,102 @@ where
         Ok(())
     }
 
+    /// Enable accelerometer interrupt on the given pin.
+    ///
+    /// Returns `Error::InvalidInputData` if the interrupt cannot be routed to the pin.
+    pub async fn acc_enable_interrupt_on(
+        &mut self,
+        pin: InterruptPin,
+        interrupt: Interrupt,
+    ) -> Result<(), Error<CommE>> {
+        match pin {
+            InterruptPin::Int1 => self.acc_enable_interrupt(interrupt).await,
+            InterruptPin::Int2 => {
+                let reg6 = self
+                    .ctrl_reg6_a
+                    .with_interrupt(interrupt)
+                    .ok_or(Error::InvalidInputData)?;
+                self.iface.write_accel_register(reg6).await?;
+                self.ctrl_reg6_a = reg6;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Disable accelerometer interrupt on the given pin.
+    ///
+    /// Returns `Error::InvalidInputData` if the interrupt cannot be routed to the pin.
+    pub async fn acc_disable_interrupt_on(
+        &mut self,
+        pin: InterruptPin,
+        interrupt: Interrupt,
+    ) -> Result<(), Error<CommE>> {
+        match pin {
+            InterruptPin::Int1 => self.acc_disable_interrupt(interrupt).await,
+            InterruptPin::Int2 => {
+                let reg6 = self
+                    .ctrl_reg6_a
+                    .without_interrupt(interrupt)
+                    .ok_or(Error::InvalidInputData)?;
+                self.iface.write_accel_register(reg6).await?;
+                self.ctrl_reg6_a = reg6;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the polarity of the accelerometer interrupt pins.
+    pub async fn acc_set_interrupt_polarity(
+        &mut self,
+        polarity: InterruptPolarity,
+    ) -> Result<(), Error<CommE>> {
+        let reg6 = self.ctrl_reg6_a.with_polarity(polarity);
+        self.iface.write_accel_register(reg6).await?;
+        self.ctrl_reg6_a = reg6;
+
+        Ok(())
+    }
+
+    /// Get the polarity of the accelerometer interrupt pins.
+    pub fn acc_interrupt_polarity(&self) -> InterruptPolarity {
+        self.ctrl_reg6_a.polarity()
+    }
+
+    /// Configure the accelerometer high-pass filter.
+    ///
+    /// In [`AccelHighPassMode::NormalWithReset`](crate::AccelHighPassMode::NormalWithReset)
+    /// mode, the filter can be reset with
+    /// [`acc_reset_high_pass_filter()`](Lsm303agr::acc_reset_high_pass_filter).
+    pub async fn acc_set_high_pass_filter(
+        &mut self,
+        config: AccelHighPassConfig,
+    ) -> Result<(), Error<CommE>> {
+        let reg2 = CtrlReg2A::from_high_pass_config(&config);
+        self.iface.write_accel_register(reg2).await?;
+        self.ctrl_reg2_a = reg2;
+
+        Ok(())
+    }
+
+    /// Reset the accelerometer high-pass filter by reading the `REFERENCE` register.
+    ///
+    /// This instantly removes the DC component from the filtered data, e.g. gravity.
+    pub async fn acc_reset_high_pass_filter(&mut self) -> Result<(), Error<CommE>> {
+        self.iface.read_accel_register::<ReferenceA>().await?;
+
+        Ok(())
+    }
+
+    /// Set the reference value used by the accelerometer high-pass filter in
+    /// [`AccelHighPassMode::Reference`](crate::AccelHighPassMode::Reference) mode.
+    pub async fn acc_set_high_pass_reference(&mut self, reference: u8) -> Result<(), Error<CommE>> {
+        self.iface
+            .write_accel_register(ReferenceA::from_bits_truncate(reference))
+            .await
+    }
+
     /// Configure the DRDY pin as a digital output.
     pub async fn mag_enable_int(&mut self) -> Result<(), Error<CommE>> {
         let regc = self.cfg_reg_c_m | CfgRegCM::INT_MAG;
@@ -163,6 +345,23 @@ where
         Ok(())
     }
 
+    /// Enable or disable the magnetometer I2C interface.
+    ///
+    /// The I2C interface is enabled by default. Disabling it is only useful when
+    /// communicating through SPI. When communicating through I2C, the magnetometer
+    /// will not respond anymore after disabling it.
+    pub async fn set_mag_i2c_enabled(&mut self, enabled: bool) -> Result<(), Error<CommE>> {
+        let regc = if enabled {
+            self.cfg_reg_c_m.difference(CfgRegCM::I2C_DIS)
+        } else {
+            self.cfg_reg_c_m.union(CfgRegCM::I2C_DIS)
+        };
+        self.iface.write_mag_register(regc).await?;
+        self.cfg_reg_c_m = regc;
+
+        Ok(())
+    }
+
     /// Enable magnetometer low-pass filter.
     pub async fn mag_enable_low_pass_filter(&mut self) -> Result<(), Error<CommE>> {
         let regb = self.cfg_reg_b_m.union(CfgRegBM::LPF);
@@ -181,6 +380,46 @@ where
         Ok(())
     }
 
+    /// Set the magnetometer set pulse frequency.
+    pub async fn set_mag_set_pulse_frequency(
+        &mut self,
+        frequency: MagSetPulseFrequency,
+    ) -> Result<(), Error<CommE>> {
+        let regb = self.cfg_reg_b_m.with_set_pulse_frequency(frequency);
+        self.iface.write_mag_register(regb).await?;
+        self.cfg_reg_b_m = regb;
+
+        Ok(())
+    }
+
+    /// Get the magnetometer set pulse frequency.
+    pub fn get_mag_set_pulse_frequency(&self) -> MagSetPulseFrequency {
+        self.cfg_reg_b_m.set_pulse_frequency()
+    }
+
+    /// Check the magnetometer threshold interrupt on the data after the
+    /// hard-iron correction.
+    ///
+    /// See [`set_mag_hard_iron_offset_nt()`](Lsm303agr::set_mag_hard_iron_offset_nt)
+    /// and [`mag_set_interrupt()`](Lsm303agr::mag_set_interrupt).
+    pub async fn mag_enable_interrupt_on_corrected_data(&mut self) -> Result<(), Error<CommE>> {
+        let regb = self.cfg_reg_b_m.union(CfgRegBM::INT_ON_DATA_OFF);
+        self.iface.write_mag_register(regb).await?;
+        self.cfg_reg_b_m = regb;
+
+        Ok(())
+    }
+
+    /// Check the magnetometer threshold interrupt on the data before the
+    /// hard-iron correction (default).
+    pub async fn mag_disable_interrupt_on_corrected_data(&mut self) -> Result<(), Error<CommE>> {
+        let regb = self.cfg_reg_b_m.difference(CfgRegBM::INT_ON_DATA_OFF);
+        self.iface.write_mag_register(regb).await?;
+        self.cfg_reg_b_m = regb;
+
+        Ok(())
+    }
+
     /// Accelerometer status
     pub async fn accel_status(&mut self) -> Result<Status, Error<CommE>> {
         self.iface
@@ -193,7 +432,9 @@ where
     pub async fn acceleration(&mut self) -> Result<Acceleration, Error<CommE>> {
         let (x, y, z) = self
             .iface
-            .read_accel_3_double_registers::<Acceleration>()
+            .read_accel_3_double_registers_with_endianness::<Acceleration>(
+                self.ctrl_reg4_a.output_endianness(),
+            )
             .await?;
 
         Ok(Acceleration {
@@ -202,9 +443,22 @@ where
             z,
             mode: self.get_accel_mode().await,
             scale: self.get_accel_scale().await,
+            axes: self.get_accel_axes().await,
         })
     }
 
+    /// Get measured acceleration corrected with the given calibration.
+    ///
+    /// Returns the acceleration in X-, Y- and Z-directions in m*g* (milli-*g*).
+    /// See [`AccelCalibrator`](crate::calibration::AccelCalibrator).
+    pub async fn acceleration_calibrated(
+        &mut self,
+        calibration: &AccelCalibration,
+    ) -> Result<(i32, i32, i32), Error<CommE>> {
+        let acceleration = self.acceleration().await?;
+        Ok(calibration.apply(&acceleration))
+    }
+
     /// Magnetometer status
     pub async fn mag_status(&mut self) -> Result<Status, Error<CommE>> {
         self.iface
@@ -225,7 +479,11 @@ where
 
     /// Get measured temperature.
     pub async fn temperature(&mut self) -> Result<Temperature, Error<CommE>> {
-        self.iface.read_accel_double_register::<Temperature>().await
+        self.iface
+            .read_accel_double_register_with_endianness::<Temperature>(
+                self.ctrl_reg4_a.output_endianness(),
+            )
+            .await
     }
 
     /// Temperature sensor status
@@ -9,7 +9,7 @@ use maybe_async_cfg::maybe;
 use crate::{
     private,
     register_address::{RegRead, RegWrite},
-    Error,
+    Endianness, Error,
 };
 
 pub(crate) const ACCEL_ADDR: u8 = 0b001_1001;
@@ -26,6 +26,8 @@ pub struct I2cInterface<I2C> {
 pub struct SpiInterface<SPIXL, SPIMAG> {
     pub(crate) spi_xl: SPIXL,
     pub(crate) spi_mag: SPIMAG,
+    /// Use half-duplex transactions for 3-wire SPI
+    pub(crate) three_wire: bool,
 }
 
 /// Write data
@@ -42,6 +44,25 @@ pub trait WriteData: private::Sealed {
 
     /// Write to an u8 magnetometer register
     async fn write_mag_register<R: RegWrite>(&mut self, reg: R) -> Result<(), Self::Error>;
+
+    /// Write a value to the accelerometer register at `address`
+    async fn write_accel_raw_register(&mut self, address: u8, value: u8)
+        -> Result<(), Self::Error>;
+
+    /// Write a value to the magnetometer register at `address`
+    async fn write_mag_raw_register(&mut self, address: u8, value: u8) -> Result<(), Self::Error>;
+
+    /// Write to an u16 magnetometer register
+    async fn write_mag_double_register<R: RegWrite<u16>>(
+        &mut self,
+        reg: R,
+    ) -> Result<(), Self::Error>;
+
+    /// Write to 3 u16 magnetometer registers
+    async fn write_mag_3_double_registers<R: RegWrite<(u16, u16, u16)>>(
+        &mut self,
+        reg: R,
+    ) -> Result<(), Self::Error>;
 }
 
 #[maybe(
@@ -55,15 +76,54 @@ where
     type Error = Error<E>;
 
     async fn write_accel_register<R: RegWrite>(&mut self, reg: R) -> Result<(), Self::Error> {
-        let payload: [u8; 2] = [R::ADDR, reg.data()];
+        self.write_accel_raw_register(R::ADDR, reg.data()).await
+    }
+
+    async fn write_mag_register<R: RegWrite>(&mut self, reg: R) -> Result<(), Self::Error> {
+        self.write_mag_raw_register(R::ADDR, reg.data()).await
+    }
+
+    async fn write_accel_raw_register(
+        &mut self,
+        address: u8,
+        value: u8,
+    ) -> Result<(), Self::Error> {
+        let payload: [u8; 2] = [address, value];
         self.i2c
             .write(ACCEL_ADDR, &payload)
             .await
             .map_err(Error::Comm)
     }
 
-    async fn write_mag_register<R: RegWrite>(&mut self, reg: R) -> Result<(), Self::Error> {
-        let payload: [u8; 2] = [R::ADDR, reg.data()];
+    async fn write_mag_raw_register(&mut self, address: u8, value: u8) -> Result<(), Self::Error> {
+        let payload: [u8; 2] = [address, value];
+        self.i2c
+            .write(MAG_ADDR, &payload)
+            .await
+            .map_err(Error::Comm)
+    }
+
+    async fn write_mag_double_register<R: RegWrite<u16>>(
+        &mut self,
+        reg: R,
+    ) -> Result<(), Self::Error> {
+        let [l, h] = reg.data().to_le_bytes();
+        let payload: [u8; 3] = [R::ADDR | 0x80, l, h];
+        self.i2c
+            .write(MAG_ADDR, &payload)
+            .await
+            .map_err(Error::Comm)
+    }
+
+    async fn write_mag_3_double_registers<R: RegWrite<(u16, u16, u16)>>(
+        &mut self,
+        reg: R,
+    ) -> Result<(), Self::Error> {
+        let (x, y, z) = reg.data();
+        let [x_l, x_h] = x.to_le_bytes();
+        let [y_l, y_h] = y.to_le_bytes();
+        let [z_l, z_h] = z.to_le_bytes();
+        let payload: [u8; 7] = [R::ADDR | 0x80, x_l, x_h, y_l, y_h, z_l, z_h];
         self.i2c
             .write(MAG_ADDR, &payload)
             .await
@@ -83,14 +143,47 @@ where
     type Error = Error<CommE>;
 
     async fn write_accel_register<R: RegWrite>(&mut self, reg: R) -> Result<(), Self::Error> {
+        self.write_accel_raw_register(R::ADDR, reg.data()).await
+    }
+
+    async fn write_mag_register<R: RegWrite>(&mut self, reg: R) -> Result<(), Self::Error> {
+        self.write_mag_raw_register(R::ADDR, reg.data()).await
+    }
+
+    async fn write_accel_raw_register(
+        &mut self,
+        address: u8,
+        value: u8,
+    ) -> Result<(), Self::Error> {
         // note that multiple byte writing needs to set the MS bit
-        let payload: [u8; 2] = [R::ADDR, reg.data()];
+        let payload: [u8; 2] = [address, value];
         self.spi_xl.write(&payload).await.map_err(Error::Comm)
     }
 
-    async fn write_mag_register<R: RegWrite>(&mut self, reg: R) -> Result<(), Self::Error> {
+    async fn write_mag_raw_register(&mut self, address: u8, value: u8) -> Result<(), Self::Error> {
         // note that multiple byte writing needs to set the MS bit
-        let payload: [u8; 2] = [R::ADDR, reg.data()];
+        let payload: [u8; 2] = [address, value];
+        self.spi_mag.write(&payload).await.map_err(Error::Comm)
+    }
+
+    async fn write_mag_double_register<R: RegWrite<u16>>(
+        &mut self,
+        reg: R,
+    ) -> Result<(), Self::Error> {
+        let [l, h] = reg.data().to_le_bytes();
+        let payload: [u8; 3] = [SPI_MS | R::ADDR, l, h];
+        self.spi_mag.write(&payload).await.map_err(Error::Comm)
+    }
+
+    async fn write_mag_3_double_registers<R: RegWrite<(u16, u16, u16)>>(
+        &mut self,
+        reg: R,
+    ) -> Result<(), Self::Error> {
+        let (x, y, z) = reg.data();
+        let [x_l, x_h] = x.to_le_bytes();
+        let [y_l, y_h] = y.to_le_bytes();
+        let [z_l, z_h] = z.to_le_bytes();
+        let payload: [u8; 7] = [SPI_MS | R::ADDR, x_l, x_h, y_l, y_h, z_l, z_h];
         self.spi_mag.write(&payload).await.map_err(Error::Comm)
     }
 }
@@ -110,20 +203,55 @@ pub trait ReadData: private::Sealed {
     /// Read an u8 magnetometer register
     async fn read_mag_register<R: RegRead>(&mut self) -> Result<R::Output, Self::Error>;
 
+    /// Read the value of the accelerometer register at `address`
+    async fn read_accel_raw_register(&mut self, address: u8) -> Result<u8, Self::Error>;
+
+    /// Read the value of the magnetometer register at `address`
+    async fn read_mag_raw_register(&mut self, address: u8) -> Result<u8, Self::Error>;
+
     /// Read an u16 accelerometer register
     async fn read_accel_double_register<R: RegRead<u16>>(
         &mut self,
     ) -> Result<R::Output, Self::Error>;
 
+    /// Read an u16 accelerometer register with the given endianness
+    async fn read_accel_double_register_with_endianness<R: RegRead<u16>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error>;
+
     /// Read 3 u16 accelerometer registers
     async fn read_accel_3_double_registers<R: RegRead<(u16, u16, u16)>>(
         &mut self,
     ) -> Result<R::Output, Self::Error>;
 
+    /// Read 3 u16 accelerometer registers with the given endianness
+    async fn read_accel_3_double_registers_with_endianness<R: RegRead<(u16, u16, u16)>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error>;
+
     /// Read 3 u16 magnetometer registers
     async fn read_mag_3_double_registers<R: RegRead<(u16, u16, u16)>>(
         &mut self,
     ) -> Result<R::Output, Self::Error>;
+
+    /// Read 3 u16 magnetometer registers with the given endianness
+    async fn read_mag_3_double_registers_with_endianness<R: RegRead<(u16, u16, u16)>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error>;
+
+    /// Read consecutive accelerometer registers starting at `address` in a single transaction
+    async fn read_accel_registers(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Read consecutive magnetometer registers starting at `address` in a single transaction
+    async fn read_mag_registers(&mut self, address: u8, data: &mut [u8])
+        -> Result<(), Self::Error>;
 }
 
 #[maybe(
@@ -144,22 +272,78 @@ where
         self.read_register::<R>(MAG_ADDR).await
     }
 
+    async fn read_accel_raw_register(&mut self, address: u8) -> Result<u8, Self::Error> {
+        self.read_raw_register(ACCEL_ADDR, address).await
+    }
+
+    async fn read_mag_raw_register(&mut self, address: u8) -> Result<u8, Self::Error> {
+        self.read_raw_register(MAG_ADDR, address).await
+    }
+
     async fn read_accel_double_register<R: RegRead<u16>>(
         &mut self,
     ) -> Result<R::Output, Self::Error> {
-        self.read_double_register::<R>(ACCEL_ADDR).await
+        self.read_accel_double_register_with_endianness::<R>(Endianness::Little)
+            .await
+    }
+
+    async fn read_accel_double_register_with_endianness<R: RegRead<u16>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error> {
+        self.read_double_register::<R>(ACCEL_ADDR, endianness).await
     }
 
     async fn read_accel_3_double_registers<R: RegRead<(u16, u16, u16)>>(
         &mut self,
     ) -> Result<R::Output, Self::Error> {
-        self.read_3_double_registers::<R>(ACCEL_ADDR).await
+        self.read_accel_3_double_registers_with_endianness::<R>(Endianness::Little)
+            .await
+    }
+
+    async fn read_accel_3_double_registers_with_endianness<R: RegRead<(u16, u16, u16)>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error> {
+        self.read_3_double_registers::<R>(ACCEL_ADDR, endianness)
+            .await
     }
 
     async fn read_mag_3_double_registers<R: RegRead<(u16, u16, u16)>>(
         &mut self,
     ) -> Result<R::Output, Self::Error> {
-        self.read_3_double_registers::<R>(MAG_ADDR).await
+        self.read_mag_3_double_registers_with_endianness::<R>(Endianness::Little)
+            .await
+    }
+
+    async fn read_mag_3_double_registers_with_endianness<R: RegRead<(u16, u16, u16)>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error> {
+        self.read_3_double_registers::<R>(MAG_ADDR, endianness)
+            .await
+    }
+
+    async fn read_accel_registers(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c
+            .write_read(ACCEL_ADDR, &[address | 0x80], data)
+            .await
+            .map_err(Error::Comm)
+    }
+
+    async fn read_mag_registers(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c
+            .write_read(MAG_ADDR, &[address | 0x80], data)
+            .await
+            .map_err(Error::Comm)
     }
 }
 
@@ -172,18 +356,25 @@ where
     I2C: i2c::I2c<Error = E>,
 {
     async fn read_register<R: RegRead>(&mut self, address: u8) -> Result<R::Output, Error<E>> {
+        let data = self.read_raw_register(address, R::ADDR).await?;
+
+        Ok(R::from_data(data))
+    }
+
+    async fn read_raw_register(&mut self, address: u8, register: u8) -> Result<u8, Error<E>> {
         let mut data = [0];
         self.i2c
-            .write_read(address, &[R::ADDR], &mut data)
+            .write_read(address, &[register], &mut data)
             .await
             .map_err(Error::Comm)?;
 
-        Ok(R::from_data(data[0]))
+        Ok(data[0])
     }
 
     async fn read_double_register<R: RegRead<u16>>(
         &mut self,
         address: u8,
+        endianness: Endianness,
     ) -> Result<R::Output, Error<E>> {
         let mut data = [0; 2];
         self.i2c
@@ -191,12 +382,13 @@ where
             .await
             .map_err(Error::Comm)?;
 
-        Ok(R::from_data(u16::from_le_bytes(data)))
+        Ok(R::from_data(endianness.u16_from_bytes(data)))
     }
 
     async fn read_3_double_registers<R: RegRead<(u16, u16, u16)>>(
         &mut self,
         address: u8,
+        endianness: Endianness,
     ) -> Result<R::Output, Error<E>> {
         let mut data = [0; 6];
         self.i2c
@@ -205,9 +397,9 @@ where
             .map_err(Error::Comm)?;
 
         Ok(R::from_data((
-            u16::from_le_bytes([data[0], data[1]]),
-            u16::from_le_bytes([data[2], data[3]]),
-            u16::from_le_bytes([data[4], data[5]]),
+            endianness.u16_from_bytes([data[0], data[1]]),
+            endianness.u16_from_bytes([data[2], data[3]]),
+            endianness.u16_from_bytes([data[4], data[5]]),
         )))
     }
 }
@@ -224,29 +416,77 @@ where
     type Error = Error<CommE>;
 
     async fn read_accel_register<R: RegRead>(&mut self) -> Result<R::Output, Self::Error> {
-        spi_read_register::<R, _, _>(&mut self.spi_xl).await
+        spi_read_register::<R, _, _>(&mut self.spi_xl, self.three_wire).await
     }
 
     async fn read_mag_register<R: RegRead>(&mut self) -> Result<R::Output, Self::Error> {
-        spi_read_register::<R, _, _>(&mut self.spi_mag).await
+        spi_read_register::<R, _, _>(&mut self.spi_mag, self.three_wire).await
+    }
+
+    async fn read_accel_raw_register(&mut self, address: u8) -> Result<u8, Self::Error> {
+        spi_read_raw_register(&mut self.spi_xl, self.three_wire, address).await
+    }
+
+    async fn read_mag_raw_register(&mut self, address: u8) -> Result<u8, Self::Error> {
+        spi_read_raw_register(&mut self.spi_mag, self.three_wire, address).await
     }
 
     async fn read_accel_double_register<R: RegRead<u16>>(
         &mut self,
     ) -> Result<R::Output, Self::Error> {
-        spi_read_double_register::<R, _, _>(&mut self.spi_xl).await
+        self.read_accel_double_register_with_endianness::<R>(Endianness::Little)
+            .await
+    }
+
+    async fn read_accel_double_register_with_endianness<R: RegRead<u16>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error> {
+        spi_read_double_register::<R, _, _>(&mut self.spi_xl, self.three_wire, endianness).await
     }
 
     async fn read_accel_3_double_registers<R: RegRead<(u16, u16, u16)>>(
         &mut self,
     ) -> Result<R::Output, Self::Error> {
-        spi_read_3_double_registers::<R, _, _>(&mut self.spi_xl).await
+        self.read_accel_3_double_registers_with_endianness::<R>(Endianness::Little)
+            .await
+    }
+
+    async fn read_accel_3_double_registers_with_endianness<R: RegRead<(u16, u16, u16)>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error> {
+        spi_read_3_double_registers::<R, _, _>(&mut self.spi_xl, self.three_wire, endianness).await
     }
 
     async fn read_mag_3_double_registers<R: RegRead<(u16, u16, u16)>>(
         &mut self,
     ) -> Result<R::Output, Self::Error> {
-        spi_read_3_double_registers::<R, _, _>(&mut self.spi_mag).await
+        self.read_mag_3_double_registers_with_endianness::<R>(Endianness::Little)
+            .await
+    }
+
+    async fn read_mag_3_double_registers_with_endianness<R: RegRead<(u16, u16, u16)>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<R::Output, Self::Error> {
+        spi_read_3_double_registers::<R, _, _>(&mut self.spi_mag, self.three_wire, endianness).await
+    }
+
+    async fn read_accel_registers(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        spi_read_registers(&mut self.spi_xl, address, data).await
+    }
+
+    async fn read_mag_registers(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        spi_read_registers(&mut self.spi_mag, address, data).await
     }
 }
 
@@ -259,13 +499,26 @@ const SPI_MS: u8 = 1 << 6;
 )]
 async fn spi_read_register<R: RegRead, SPI: spi::SpiDevice<u8, Error = CommE>, CommE>(
     spi: &mut SPI,
+    three_wire: bool,
 ) -> Result<R::Output, Error<CommE>> {
-    let mut data = [SPI_RW | R::ADDR, 0];
-    spi.transfer_in_place(&mut data)
-        .await
-        .map_err(Error::Comm)?;
+    let data = spi_read_raw_register(spi, three_wire, R::ADDR).await?;
+
+    Ok(R::from_data(data))
+}
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+async fn spi_read_raw_register<SPI: spi::SpiDevice<u8, Error = CommE>, CommE>(
+    spi: &mut SPI,
+    three_wire: bool,
+    address: u8,
+) -> Result<u8, Error<CommE>> {
+    let mut data = [0];
+    spi_read_into(spi, three_wire, SPI_RW | address, &mut data).await?;
 
-    Ok(R::from_data(data[1]))
+    Ok(data[0])
 }
 
 #[maybe(
@@ -278,13 +531,13 @@ async fn spi_read_double_register<
     CommE,
 >(
     spi: &mut SPI,
+    three_wire: bool,
+    endianness: Endianness,
 ) -> Result<R::Output, Error<CommE>> {
-    let mut data = [SPI_RW | SPI_MS | R::ADDR, 0, 0];
-    spi.transfer_in_place(&mut data)
-        .await
-        .map_err(Error::Comm)?;
+    let mut data = [0; 2];
+    spi_read_into(spi, three_wire, SPI_RW | SPI_MS | R::ADDR, &mut data).await?;
 
-    Ok(R::from_data(u16::from_le_bytes([data[1], data[2]])))
+    Ok(R::from_data(endianness.u16_from_bytes(data)))
 }
 
 #[maybe(
@@ -297,15 +550,65 @@ async fn spi_read_3_double_registers<
     CommE,
 >(
     spi: &mut SPI,
+    three_wire: bool,
+    endianness: Endianness,
 ) -> Result<R::Output, Error<CommE>> {
-    let mut data = [SPI_RW | SPI_MS | R::ADDR, 0, 0, 0, 0, 0, 0];
-    spi.transfer_in_place(&mut data)
-        .await
-        .map_err(Error::Comm)?;
+    let mut data = [0; 6];
+    spi_read_into(spi, three_wire, SPI_RW | SPI_MS | R::ADDR, &mut data).await?;
 
     Ok(R::from_data((
-        u16::from_le_bytes([data[1], data[2]]),
-        u16::from_le_bytes([data[3], data[4]]),
-        u16::from_le_bytes([data[5], data[6]]),
+        endianness.u16_from_bytes([data[0], data[1]]),
+        endianness.u16_from_bytes([data[2], data[3]]),
+        endianness.u16_from_bytes([data[4], data[5]]),
     )))
 }
+
+/// Send `command` and read up to 6 bytes into `data`.
+///
+/// In 3-wire mode the data line is shared, so the command is written
+/// and the data read in separate half-duplex operations.
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+async fn spi_read_into<SPI: spi::SpiDevice<u8, Error = CommE>, CommE>(
+    spi: &mut SPI,
+    three_wire: bool,
+    command: u8,
+    data: &mut [u8],
+) -> Result<(), Error<CommE>> {
+    if three_wire {
+        return spi
+            .transaction(&mut [
+                spi::Operation::Write(&[command]),
+                spi::Operation::Read(data),
+            ])
+            .await
+            .map_err(Error::Comm);
+    }
+
+    let mut buffer = [0; 7];
+    let buffer = &mut buffer[..=data.len()];
+    buffer[0] = command;
+    spi.transfer_in_place(buffer).await.map_err(Error::Comm)?;
+    data.copy_from_slice(&buffer[1..]);
+
+    Ok(())
+}
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+async fn spi_read_registers<SPI: spi::SpiDevice<u8, Error = CommE>, CommE>(
+    spi: &mut SPI,
+    address: u8,
+    data: &mut [u8],
+) -> Result<(), Error<CommE>> {
+    spi.transaction(&mut [
+        spi::Operation::Write(&[SPI_RW | SPI_MS | address]),
+        spi::Operation::Read(data),
+    ])
+    .await
+    .map_err(Error::Comm)
+}
@@ -2,22 +2,40 @@
 //! high-performance eCompass module: ultra-low-power 3D accelerometer and
 //! 3D magnetometer using the [`embedded-hal`] traits.
 //! This driver also supports the [`embedded-hal-async`] traits if the `async` feature is enabled.
+//! The tilt-compensated eCompass in the `compass` module is available if the `libm` feature is enabled.
 //!
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
 //!
 //! This driver allows you to:
 //! - Connect through I2C or SPI. See: [`new_with_i2c()`](Lsm303agr::new_with_i2c) and [`new_with_spi()`](Lsm303agr::new_with_spi) .
+//! - Connect through 3-wire SPI. See: [`new_with_spi_3wire()`](Lsm303agr::new_with_spi_3wire).
+//! - Attach to an already configured device. See: [`attach()`](Lsm303agr::attach).
+//! - Save and restore the device configuration. See: [`apply_config()`](Lsm303agr::apply_config).
 //! - Initialize the device. See: [`init()`](Lsm303agr::init).
+//! - Reset the accelerometer and/or magnetometer. See: [`reset_all()`](Lsm303agr::reset_all).
+//! - Read and write registers directly. See: [`read_accel_reg()`](Lsm303agr::read_accel_reg) and [`write_accel_reg()`](Lsm303agr::write_accel_reg).
+//! - Dump all registers for diagnostics. See: [`dump_registers()`](Lsm303agr::dump_registers).
 //! - Accelerometer:
 //!     - Read measured acceleration. See: [`acceleration()`](Lsm303agr::acceleration).
+//!     - Calibrate the accelerometer offset and gain. See: [`acceleration_calibrated()`](Lsm303agr::acceleration_calibrated).
 //!     - Get accelerometer status. See: [`accel_status()`](Lsm303agr::accel_status).
 //!     - Set accelerometer mode and output data rate. See: [`set_accel_mode_and_odr()`](Lsm303agr::set_accel_mode_and_odr).
 //!     - Set accelerometer scale. See: [`set_accel_scale()`](Lsm303agr::set_accel_scale).
+//!     - Enable/disable accelerometer axes. See: [`set_accel_axes()`](Lsm303agr::set_accel_axes).
+//!     - Set accelerometer output data endianness. See: [`set_accel_endianness()`](Lsm303agr::set_accel_endianness).
 //!     - Get accelerometer ID. See: [`accelerometer_id()`](Lsm303agr::accelerometer_id).
 //!     - Get temperature sensor status. See: [`temperature_status()`](Lsm303agr::temperature_status).
 //!     - Read measured temperature. See: [`temperature()`](Lsm303agr::temperature).
 //!     - Configure FIFO. See: [`acc_set_fifo_mode()`](Lsm303agr::acc_set_fifo_mode).
+//!     - Read FIFO status and contents. See: [`read_fifo()`](Lsm303agr::read_fifo).
 //!     - Enable/disable interrupts. See: [`acc_enable_interrupt()`](Lsm303agr::acc_enable_interrupt).
+//!     - Enable/disable interrupts on the INT1 or INT2 pin. See: [`acc_enable_interrupt_on()`](Lsm303agr::acc_enable_interrupt_on).
+//!     - Set the interrupt pin polarity. See: [`acc_set_interrupt_polarity()`](Lsm303agr::acc_set_interrupt_polarity).
+//!     - Configure the inertial interrupt generators. See: [`acc_set_inertial_interrupt()`](Lsm303agr::acc_set_inertial_interrupt).
+//!     - Configure click (tap) detection. See: [`acc_set_click_detection()`](Lsm303agr::acc_set_click_detection).
+//!     - Configure sleep-to-wake activity detection. See: [`set_activity_detection()`](Lsm303agr::set_activity_detection).
+//!     - Configure the high-pass filter. See: [`acc_set_high_pass_filter()`](Lsm303agr::acc_set_high_pass_filter).
+//!     - Run the accelerometer self-test. See: [`accel_self_test()`](Lsm303agr::accel_self_test).
 //! - Magnetometer:
 //!     - Get the magnetometer status. See: [`mag_status()`](Lsm303agr::mag_status).
 //!     - Change into continuous/one-shot mode. See: [`into_mag_continuous()`](Lsm303agr::into_mag_continuous).
@@ -25,7 +43,17 @@
 //!     - Set magnetometer mode and output data rate. See: [`set_mag_mode_and_odr()`](Lsm303agr::set_mag_mode_and_odr).
 //!     - Get magnetometer ID. See: [`magnetometer_id()`](Lsm303agr::magnetometer_id).
 //!     - Enable/disable magnetometer built in offset cancellation. See: [`enable_mag_offset_cancellation()`](Lsm303agr::enable_mag_offset_cancellation).
+//!     - Enable/disable magnetometer temperature compensation. See: [`enable_mag_temperature_compensation()`](Lsm303agr::enable_mag_temperature_compensation).
+//!     - Set magnetometer output data endianness. See: [`set_mag_endianness()`](Lsm303agr::set_mag_endianness).
+//!     - Enable/disable the magnetometer I2C interface when using SPI. See: [`set_mag_i2c_enabled()`](Lsm303agr::set_mag_i2c_enabled).
+//!     - Set magnetometer hard-iron offset. See: [`set_mag_hard_iron_offset_nt()`](Lsm303agr::set_mag_hard_iron_offset_nt).
+//!     - Calibrate the magnetometer hard-iron and soft-iron distortions. See: [`MagCalibrator`](calibration::MagCalibrator).
+//!     - Configure the magnetometer threshold interrupt. See: [`mag_set_interrupt()`](Lsm303agr::mag_set_interrupt).
+//!     - Run the magnetometer self-test. See: [`mag_self_test()`](Lsm303agr::mag_self_test).
 //!     - Enable/disable magnetometer low-pass filter. See: [`mag_enable_low_pass_filter()`](Lsm303agr::mag_enable_low_pass_filter).
+//!     - Set magnetometer set pulse frequency. See: [`set_mag_set_pulse_frequency()`](Lsm303agr::set_mag_set_pulse_frequency).
+//!     - Check the magnetometer interrupt on hard-iron corrected data. See: [`mag_enable_interrupt_on_corrected_data()`](Lsm303agr::mag_enable_interrupt_on_corrected_data).
+//! - Compute the tilt-compensated eCompass heading. See: `compass::orientation()`.
 //!
 //! <!-- TODO
 //! [Introductory blog post](TODO)
@@ -110,21 +138,35 @@
 #![no_std]
 
 use core::marker::PhantomData;
+mod accel_interrupts;
 mod accel_mode_and_odr;
+mod attach;
+pub mod calibration;
+#[cfg(feature = "libm")]
+pub mod compass;
+mod config;
 mod device_impl;
 pub mod interface;
 mod mag_mode_change;
 mod magnetometer;
+mod register_access;
+mod register_dump;
+mod reset;
+mod self_test;
 mod types;
 pub use crate::types::{
-    mode, AccelMode, AccelOutputDataRate, AccelScale, Acceleration, AccelerometerId, Error,
-    FifoMode, Interrupt, MagMode, MagOutputDataRate, MagneticField, MagnetometerId,
-    ModeChangeError, Status, Temperature, TemperatureStatus,
+    mode, AccelHighPassConfig, AccelHighPassCutoff, AccelHighPassMode, AccelMode,
+    AccelOutputDataRate, AccelScale, Acceleration, AccelerometerId, AxisSet, ClickConfig,
+    ClickSource, DeviceConfig, Endianness, Error, FifoMode, FifoStatus, InertialInterruptConfig,
+    InertialInterruptMode, Interrupt, InterruptGenerator, InterruptPin, InterruptPolarity,
+    InterruptSource, MagInterruptConfig, MagInterruptSource, MagMode, MagModeDevice,
+    MagOutputDataRate, MagSetPulseFrequency, MagneticField, MagnetometerId, ModeChangeError,
+    RegisterDump, SelfTestReport, Status, Temperature, TemperatureStatus,
 };
 mod register_address;
 use crate::register_address::{
-    CfgRegAM, CfgRegBM, CfgRegCM, CtrlReg1A, CtrlReg3A, CtrlReg4A, CtrlReg5A, FifoCtrlRegA,
-    TempCfgRegA,
+    CfgRegAM, CfgRegBM, CfgRegCM, CtrlReg1A, CtrlReg2A, CtrlReg3A, CtrlReg4A, CtrlReg5A, CtrlReg6A,
+    FifoCtrlRegA, IntCtrlRegM, TempCfgRegA,
 };
 
 /// LSM303AGR device driver
@@ -133,12 +175,15 @@ pub struct Lsm303agr<DI, MODE> {
     /// Digital interface: I2C or SPI
     iface: DI,
     ctrl_reg1_a: CtrlReg1A,
+    ctrl_reg2_a: CtrlReg2A,
     ctrl_reg3_a: CtrlReg3A,
     ctrl_reg4_a: CtrlReg4A,
     ctrl_reg5_a: CtrlReg5A,
+    ctrl_reg6_a: CtrlReg6A,
     cfg_reg_a_m: CfgRegAM,
     cfg_reg_b_m: CfgRegBM,
     cfg_reg_c_m: CfgRegCM,
+    int_ctrl_reg_m: IntCtrlRegM,
     temp_cfg_reg_a: TempCfgRegA,
     fifo_ctrl_reg_a: FifoCtrlRegA,
     accel_odr: Option<AccelOutputDataRate>,
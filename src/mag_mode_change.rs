@@ -5,6 +5,29 @@ use crate::{
     mode, Error, Lsm303agr, ModeChangeError, PhantomData,
 };
 
+impl<DI, MODE> Lsm303agr<DI, MODE> {
+    /// Change the magnetometer mode marker type, keeping the cached registers.
+    pub(crate) fn with_mag_mode<NEWMODE>(self) -> Lsm303agr<DI, NEWMODE> {
+        Lsm303agr {
+            iface: self.iface,
+            ctrl_reg1_a: self.ctrl_reg1_a,
+            ctrl_reg2_a: self.ctrl_reg2_a,
+            ctrl_reg3_a: self.ctrl_reg3_a,
+            ctrl_reg4_a: self.ctrl_reg4_a,
+            ctrl_reg5_a: self.ctrl_reg5_a,
+            ctrl_reg6_a: self.ctrl_reg6_a,
+            cfg_reg_a_m: self.cfg_reg_a_m,
+            cfg_reg_b_m: self.cfg_reg_b_m,
+            cfg_reg_c_m: self.cfg_reg_c_m,
+            int_ctrl_reg_m: self.int_ctrl_reg_m,
+            temp_cfg_reg_a: self.temp_cfg_reg_a,
+            fifo_ctrl_reg_a: self.fifo_ctrl_reg_a,
+            accel_odr: self.accel_odr,
+            _mag_mode: PhantomData,
+        }
+    }
+}
+
 #[maybe(
     sync(cfg(not(feature = "async")), keep_self,),
     async(cfg(feature = "async"), keep_self,)
@@ -20,20 +43,10 @@ where
         let cfg = self.cfg_reg_a_m.continuous_mode();
         match self.iface.write_mag_register(cfg).await {
             Err(error) => Err(ModeChangeError { error, dev: self }),
-            Ok(_) => Ok(Lsm303agr {
-                iface: self.iface,
-                ctrl_reg1_a: self.ctrl_reg1_a,
-                ctrl_reg3_a: self.ctrl_reg3_a,
-                ctrl_reg4_a: self.ctrl_reg4_a,
-                ctrl_reg5_a: self.ctrl_reg5_a,
-                cfg_reg_a_m: cfg,
-                cfg_reg_b_m: self.cfg_reg_b_m,
-                cfg_reg_c_m: self.cfg_reg_c_m,
-                temp_cfg_reg_a: self.temp_cfg_reg_a,
-                fifo_ctrl_reg_a: self.fifo_ctrl_reg_a,
-                accel_odr: None,
-                _mag_mode: PhantomData,
-            }),
+            Ok(_) => {
+                self.cfg_reg_a_m = cfg;
+                Ok(self.with_mag_mode())
+            }
         }
     }
 }
@@ -56,20 +69,10 @@ where
         let cfg = self.cfg_reg_a_m.idle_mode();
         match self.iface.write_mag_register(cfg).await {
             Err(error) => Err(ModeChangeError { error, dev: self }),
-            Ok(_) => Ok(Lsm303agr {
-                iface: self.iface,
-                ctrl_reg1_a: self.ctrl_reg1_a,
-                ctrl_reg3_a: self.ctrl_reg3_a,
-                ctrl_reg4_a: self.ctrl_reg4_a,
-                ctrl_reg5_a: self.ctrl_reg5_a,
-                cfg_reg_a_m: cfg,
-                cfg_reg_b_m: self.cfg_reg_b_m,
-                cfg_reg_c_m: self.cfg_reg_c_m,
-                temp_cfg_reg_a: self.temp_cfg_reg_a,
-                fifo_ctrl_reg_a: self.fifo_ctrl_reg_a,
-                accel_odr: None,
-                _mag_mode: PhantomData,
-            }),
+            Ok(_) => {
+                self.cfg_reg_a_m = cfg;
+                Ok(self.with_mag_mode())
+            }
         }
     }
 }
@@ -6,10 +6,14 @@ use embedded_hal::delay::DelayNs;
 use embedded_hal_async::delay::DelayNs;
 
 use crate::{
+    calibration::MagCalibration,
     interface::{ReadData, WriteData},
     mode,
-    register_address::{CfgRegAM, CfgRegBM},
-    Error, Lsm303agr, MagMode, MagOutputDataRate, MagneticField,
+    register_address::{
+        CfgRegAM, CfgRegBM, CfgRegCM, IntCtrlRegM, IntSourceRegM, IntThsRegM, OffsetRegM,
+    },
+    Endianness, Error, Lsm303agr, MagInterruptConfig, MagInterruptSource, MagMode,
+    MagOutputDataRate, MagneticField,
 };
 
 #[maybe(
@@ -61,6 +65,134 @@ where
     pub fn get_mag_mode(&self) -> MagMode {
         self.cfg_reg_a_m.mode()
     }
+
+    /// Enable the magnetometer's temperature compensation.
+    ///
+    /// According to the [datasheet] this must be enabled for the correct
+    /// operation of the magnetometer. [`init()`](Lsm303agr::init) enables it.
+    ///
+    /// [datasheet]: https://www.st.com/resource/en/datasheet/lsm303agr.pdf
+    pub async fn enable_mag_temperature_compensation(&mut self) -> Result<(), Error<CommE>> {
+        let reg_a = self.cfg_reg_a_m | CfgRegAM::COMP_TEMP_EN;
+
+        self.iface.write_mag_register(reg_a).await?;
+        self.cfg_reg_a_m = reg_a;
+
+        Ok(())
+    }
+
+    /// Disable the magnetometer's temperature compensation.
+    pub async fn disable_mag_temperature_compensation(&mut self) -> Result<(), Error<CommE>> {
+        let reg_a = self.cfg_reg_a_m & !CfgRegAM::COMP_TEMP_EN;
+
+        self.iface.write_mag_register(reg_a).await?;
+        self.cfg_reg_a_m = reg_a;
+
+        Ok(())
+    }
+
+    /// Set the magnetometer output data endianness.
+    ///
+    /// The measurements are decoded according to this setting.
+    pub async fn set_mag_endianness(&mut self, endianness: Endianness) -> Result<(), Error<CommE>> {
+        let reg_c = self.cfg_reg_c_m.with_endianness(endianness);
+
+        self.iface.write_mag_register(reg_c).await?;
+        self.cfg_reg_c_m = reg_c;
+
+        Ok(())
+    }
+
+    /// Get the magnetometer output data endianness.
+    pub fn get_mag_endianness(&self) -> Endianness {
+        self.cfg_reg_c_m.endianness()
+    }
+
+    /// Set the magnetometer hard-iron offset in raw LSB.
+    ///
+    /// The sensor subtracts these values from the measured magnetic field
+    /// before storing it in the output registers.
+    pub async fn set_mag_hard_iron_offset_raw(
+        &mut self,
+        x: i16,
+        y: i16,
+        z: i16,
+    ) -> Result<(), Error<CommE>> {
+        self.iface
+            .write_mag_3_double_registers(OffsetRegM { x, y, z })
+            .await
+    }
+
+    /// Set the magnetometer hard-iron offset in nT (nano-Tesla).
+    ///
+    /// The values are rounded to the nearest LSB (150 nT).
+    pub async fn set_mag_hard_iron_offset_nt(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+    ) -> Result<(), Error<CommE>> {
+        self.set_mag_hard_iron_offset_raw(
+            MagneticField::raw_from_nt(x),
+            MagneticField::raw_from_nt(y),
+            MagneticField::raw_from_nt(z),
+        )
+        .await
+    }
+
+    /// Get the magnetometer hard-iron offset.
+    pub async fn mag_hard_iron_offset(&mut self) -> Result<MagneticField, Error<CommE>> {
+        // The endianness selection only applies to the output data registers.
+        self.iface.read_mag_3_double_registers::<OffsetRegM>().await
+    }
+
+    /// Write the hard-iron offset of a magnetometer calibration into the sensor.
+    ///
+    /// The sensor cannot correct soft-iron distortions, so the measurements
+    /// must then be corrected with [`MagCalibration::apply_soft_iron()`].
+    pub async fn set_mag_calibration(
+        &mut self,
+        calibration: &MagCalibration,
+    ) -> Result<(), Error<CommE>> {
+        let (x, y, z) = calibration.hard_iron_offset_raw();
+        self.set_mag_hard_iron_offset_raw(x, y, z).await
+    }
+
+    /// Configure the magnetometer threshold interrupt.
+    ///
+    /// The interrupt is enabled if at least one axis is enabled in the configuration.
+    pub async fn mag_set_interrupt(
+        &mut self,
+        config: MagInterruptConfig,
+    ) -> Result<(), Error<CommE>> {
+        let threshold = MagneticField::raw_from_nt(config.threshold_nt.min(i32::MAX as u32) as i32);
+        self.iface
+            .write_mag_double_register(IntThsRegM(threshold as u16))
+            .await?;
+
+        let mut ctrl = config.ctrl;
+        ctrl.set(IntCtrlRegM::IEN, ctrl.intersects(IntCtrlRegM::AXES));
+        self.iface.write_mag_register(ctrl).await?;
+        self.int_ctrl_reg_m = ctrl;
+
+        let mut regc = self.cfg_reg_c_m;
+        regc.set(CfgRegCM::INT_MAG_PIN, config.int_mag_pin);
+        self.iface.write_mag_register(regc).await?;
+        self.cfg_reg_c_m = regc;
+
+        Ok(())
+    }
+
+    /// Read the magnetometer threshold interrupt source.
+    ///
+    /// Reading the source clears a latched interrupt.
+    pub async fn mag_interrupt_source(&mut self) -> Result<MagInterruptSource, Error<CommE>> {
+        let flags = self.iface.read_mag_register::<IntSourceRegM>().await?;
+        Ok(MagInterruptSource::new(
+            flags,
+            self.int_ctrl_reg_m.polarity(),
+        ))
+    }
 }
 
 #[maybe(
@@ -74,7 +206,9 @@ where
     /// Get the measured magnetic field.
     pub async fn magnetic_field(&mut self) -> Result<MagneticField, Error<CommE>> {
         self.iface
-            .read_mag_3_double_registers::<MagneticField>()
+            .read_mag_3_double_registers_with_endianness::<MagneticField>(
+                self.cfg_reg_c_m.endianness(),
+            )
             .await
     }
 
@@ -135,7 +269,9 @@ where
         if status.xyz_new_data() {
             Ok(self
                 .iface
-                .read_mag_3_double_registers::<MagneticField>()
+                .read_mag_3_double_registers_with_endianness::<MagneticField>(
+                    self.cfg_reg_c_m.endianness(),
+                )
                 .await?)
         } else {
             let cfg = self.iface.read_mag_register::<CfgRegAM>().await?;
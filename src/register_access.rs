@@ -0,0 +1,100 @@
+use maybe_async_cfg::maybe;
+
+use crate::{
+    interface::{ReadData, WriteData},
+    Error, Lsm303agr,
+};
+
+/// Highest accelerometer register address. The two most significant bits of
+/// the SPI sub-address are used for read/write and auto-increment selection.
+const MAX_ACCEL_REGISTER_ADDRESS: u8 = 0x3F;
+
+/// Highest magnetometer register address. The most significant bit of the
+/// sub-address is used for auto-increment (I2C) or read/write selection (SPI).
+const MAX_MAG_REGISTER_ADDRESS: u8 = 0x7F;
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE, MODE> Lsm303agr<DI, MODE>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    /// Read the accelerometer register at `address`.
+    ///
+    /// This bypasses the driver entirely. Please refer to the [datasheet]
+    /// for the register map.
+    ///
+    /// Returns `Error::InvalidInputData` if the address is greater than `0x3F`.
+    ///
+    /// [datasheet]: https://www.st.com/resource/en/datasheet/lsm303agr.pdf
+    pub async fn read_accel_reg(&mut self, address: u8) -> Result<u8, Error<CommE>> {
+        check_address(address, MAX_ACCEL_REGISTER_ADDRESS)?;
+        self.iface.read_accel_raw_register(address).await
+    }
+
+    /// Write `value` to the accelerometer register at `address`.
+    ///
+    /// This bypasses the register cache of the driver, so the driver will not
+    /// know about the new value and may overwrite it on later configuration changes.
+    /// Call [`refresh_cache()`](Lsm303agr::refresh_cache) after writing
+    /// configuration registers.
+    ///
+    /// Returns `Error::InvalidInputData` if the address is greater than `0x3F`.
+    pub async fn write_accel_reg(&mut self, address: u8, value: u8) -> Result<(), Error<CommE>> {
+        check_address(address, MAX_ACCEL_REGISTER_ADDRESS)?;
+        self.iface.write_accel_raw_register(address, value).await
+    }
+
+    /// Read the magnetometer register at `address`.
+    ///
+    /// This bypasses the driver entirely. Please refer to the [datasheet]
+    /// for the register map.
+    ///
+    /// Returns `Error::InvalidInputData` if the address is greater than `0x7F`.
+    ///
+    /// [datasheet]: https://www.st.com/resource/en/datasheet/lsm303agr.pdf
+    pub async fn read_mag_reg(&mut self, address: u8) -> Result<u8, Error<CommE>> {
+        check_address(address, MAX_MAG_REGISTER_ADDRESS)?;
+        self.iface.read_mag_raw_register(address).await
+    }
+
+    /// Write `value` to the magnetometer register at `address`.
+    ///
+    /// This bypasses the register cache of the driver, so the driver will not
+    /// know about the new value and may overwrite it on later configuration changes.
+    /// Call [`refresh_cache()`](Lsm303agr::refresh_cache) after writing
+    /// configuration registers.
+    ///
+    /// Writing the magnetometer mode (`MD` bits of `CFG_REG_A_M`) this way does not
+    /// change the magnetometer mode type of the driver.
+    ///
+    /// Returns `Error::InvalidInputData` if the address is greater than `0x7F`.
+    pub async fn write_mag_reg(&mut self, address: u8, value: u8) -> Result<(), Error<CommE>> {
+        check_address(address, MAX_MAG_REGISTER_ADDRESS)?;
+        self.iface.write_mag_raw_register(address, value).await
+    }
+
+    /// Read the configuration registers from the device and update the
+    /// register cache of the driver.
+    ///
+    /// This is necessary after writing configuration registers with
+    /// [`write_accel_reg()`](Lsm303agr::write_accel_reg) or
+    /// [`write_mag_reg()`](Lsm303agr::write_mag_reg).
+    ///
+    /// The magnetometer mode type of the driver is not changed.
+    /// Use [`attach()`](Lsm303agr::attach) to obtain a driver in the mode
+    /// the device is configured in.
+    pub async fn refresh_cache(&mut self) -> Result<(), Error<CommE>> {
+        self.read_cached_registers().await
+    }
+}
+
+fn check_address<CommE>(address: u8, max: u8) -> Result<(), Error<CommE>> {
+    if address > max {
+        Err(Error::InvalidInputData)
+    } else {
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use crate::types::{
-    AccelOutputDataRate, AccelScale, AccelerometerId, FifoMode, Interrupt, MagMode,
-    MagOutputDataRate, MagnetometerId, StatusFlags,
+    AccelHighPassConfig, AccelHighPassCutoff, AccelHighPassMode, AccelOutputDataRate, AccelScale,
+    AccelerometerId, AxisSet, Endianness, FifoMode, InertialInterruptMode, Interrupt,
+    InterruptGenerator, InterruptPolarity, MagMode, MagOutputDataRate, MagSetPulseFrequency,
+    MagneticField, MagnetometerId, StatusFlags,
 };
 
 pub trait RegRead<D = u8> {
@@ -86,7 +88,7 @@ impl WhoAmIA {
 
 register! {
   /// TEMP_CFG_REG_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct TempCfgRegA: 0x1F {
     const TEMP_EN1 = 0b10000000;
     const TEMP_EN0 = 0b01000000;
@@ -97,7 +99,7 @@ register! {
 
 register! {
   /// CTRL_REG1_A
-  #[derive(Debug, Copy, Clone)]
+  #[derive(Debug, Copy, Clone, PartialEq)]
   pub struct CtrlReg1A: 0x20 {
     const ODR3 = 0b10000000;
     const ODR2 = 0b01000000;
@@ -109,6 +111,7 @@ register! {
     const XEN  = 0b00000001;
 
     const ODR = Self::ODR3.bits() | Self::ODR2.bits() | Self::ODR1.bits() | Self::ODR0.bits();
+    const AXES = Self::ZEN.bits() | Self::YEN.bits() | Self::XEN.bits();
   }
 }
 
@@ -140,10 +143,37 @@ impl CtrlReg1A {
             }
         }
     }
+
+    pub const fn axes(&self) -> AxisSet {
+        AxisSet::from_bits_truncate(self.intersection(Self::AXES).bits())
+    }
+
+    pub const fn with_axes(self, axes: AxisSet) -> Self {
+        self.difference(Self::AXES)
+            .union(Self::from_bits_truncate(axes.bits()))
+    }
+
+    pub const fn odr(&self) -> Option<AccelOutputDataRate> {
+        Some(match self.intersection(Self::ODR).bits() >> 4 {
+            0b0000 => return None,
+            0b0001 => AccelOutputDataRate::Hz1,
+            0b0010 => AccelOutputDataRate::Hz10,
+            0b0011 => AccelOutputDataRate::Hz25,
+            0b0100 => AccelOutputDataRate::Hz50,
+            0b0101 => AccelOutputDataRate::Hz100,
+            0b0110 => AccelOutputDataRate::Hz200,
+            0b0111 => AccelOutputDataRate::Hz400,
+            0b1000 if self.contains(Self::LPEN) => AccelOutputDataRate::Khz1_620LowPower,
+            0b1001 if self.contains(Self::LPEN) => AccelOutputDataRate::Khz5_376LowPower,
+            0b1001 => AccelOutputDataRate::Khz1_344,
+            _ => return None,
+        })
+    }
 }
 
 register! {
   /// CTRL_REG2_A
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct CtrlReg2A: 0x21 {
     const HPM1    = 0b10000000;
     const HPM0    = 0b01000000;
@@ -153,12 +183,46 @@ register! {
     const HPCLICK = 0b00000100;
     const HPIS2   = 0b00000010;
     const HPIS1   = 0b00000001;
+
+    const HPM = Self::HPM1.bits() | Self::HPM0.bits();
+    const HPCF = Self::HPCF2.bits() | Self::HPCF1.bits();
   }
 }
 
+impl CtrlReg2A {
+    pub const fn with_mode(self, mode: AccelHighPassMode) -> Self {
+        match mode {
+            AccelHighPassMode::NormalWithReset => self.difference(Self::HPM),
+            AccelHighPassMode::Reference => self.difference(Self::HPM1).union(Self::HPM0),
+            AccelHighPassMode::Normal => self.union(Self::HPM1).difference(Self::HPM0),
+            AccelHighPassMode::AutoResetOnInterrupt => self.union(Self::HPM),
+        }
+    }
+
+    pub const fn with_cutoff(self, cutoff: AccelHighPassCutoff) -> Self {
+        match cutoff {
+            AccelHighPassCutoff::Highest => self.difference(Self::HPCF),
+            AccelHighPassCutoff::High => self.difference(Self::HPCF2).union(Self::HPCF1),
+            AccelHighPassCutoff::Low => self.union(Self::HPCF2).difference(Self::HPCF1),
+            AccelHighPassCutoff::Lowest => self.union(Self::HPCF),
+        }
+    }
+
+    pub fn from_high_pass_config(config: &AccelHighPassConfig) -> Self {
+        let mut reg = Self::default()
+            .with_mode(config.mode)
+            .with_cutoff(config.cutoff);
+        reg.set(Self::FDS, config.output_data);
+        reg.set(Self::HPCLICK, config.click);
+        reg.set(Self::HPIS1, config.aoi1);
+        reg.set(Self::HPIS2, config.aoi2);
+        reg
+    }
+}
+
 register! {
   /// CTRL_REG3_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct CtrlReg3A: 0x22 {
     const I1_CLICK   = 0b10000000;
     const I1_AOI1    = 0b01000000;
@@ -198,7 +262,7 @@ impl CtrlReg3A {
 
 register! {
   /// CTRL_REG4_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct CtrlReg4A: 0x23 {
     const BDU        = 0b10000000;
     const BLE        = 0b01000000;
@@ -232,11 +296,37 @@ impl CtrlReg4A {
             AccelScale::G16 => self.union(Self::FS),
         }
     }
+
+    pub const fn endianness(&self) -> Endianness {
+        if self.contains(Self::BLE) {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+
+    pub const fn with_endianness(self, endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::Little => self.difference(Self::BLE),
+            Endianness::Big => self.union(Self::BLE),
+        }
+    }
+
+    /// Endianness of the output data.
+    ///
+    /// The BLE selection only takes effect in high-resolution mode.
+    pub const fn output_endianness(&self) -> Endianness {
+        if self.contains(Self::HR) {
+            self.endianness()
+        } else {
+            Endianness::Little
+        }
+    }
 }
 
 register! {
   /// CTRL_REG5_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct CtrlReg5A: 0x24 {
     const BOOT     = 0b10000000;
     const FIFO_EN  = 0b01000000;
@@ -247,9 +337,32 @@ register! {
   }
 }
 
+impl CtrlReg5A {
+    pub const fn with_inertial_interrupt(
+        self,
+        generator: InterruptGenerator,
+        latched: bool,
+        mode: InertialInterruptMode,
+    ) -> Self {
+        let (lir, d4d) = match generator {
+            InterruptGenerator::Aoi1 => (Self::LIR_INT1, Self::D4D_INT1),
+            InterruptGenerator::Aoi2 => (Self::LIR_INT2, Self::D4D_INT2),
+        };
+        let reg = if latched {
+            self.union(lir)
+        } else {
+            self.difference(lir)
+        };
+        match mode {
+            InertialInterruptMode::Movement4D | InertialInterruptMode::Position4D => reg.union(d4d),
+            _ => reg.difference(d4d),
+        }
+    }
+}
+
 register! {
   /// CTRL_REG6_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct CtrlReg6A: 0x25 {
     const I2_CLICK_EN = 0b10000000;
     const I2_INT1     = 0b01000000;
@@ -260,6 +373,49 @@ register! {
   }
 }
 
+register! {
+  /// REFERENCE/DATACAPTURE_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct ReferenceA: 0x26 {
+    const REF = 0b11111111;
+  }
+}
+
+impl CtrlReg6A {
+    pub const fn with_interrupt(self, interrupt: Interrupt) -> Option<Self> {
+        match interrupt {
+            Interrupt::Click => Some(self.union(Self::I2_CLICK_EN)),
+            Interrupt::Aoi1 => Some(self.union(Self::I2_INT1)),
+            Interrupt::Aoi2 => Some(self.union(Self::I2_INT2)),
+            _ => None,
+        }
+    }
+
+    pub const fn without_interrupt(self, interrupt: Interrupt) -> Option<Self> {
+        match interrupt {
+            Interrupt::Click => Some(self.difference(Self::I2_CLICK_EN)),
+            Interrupt::Aoi1 => Some(self.difference(Self::I2_INT1)),
+            Interrupt::Aoi2 => Some(self.difference(Self::I2_INT2)),
+            _ => None,
+        }
+    }
+
+    pub const fn polarity(&self) -> InterruptPolarity {
+        if self.contains(Self::H_LACTIVE) {
+            InterruptPolarity::ActiveLow
+        } else {
+            InterruptPolarity::ActiveHigh
+        }
+    }
+
+    pub const fn with_polarity(self, polarity: InterruptPolarity) -> Self {
+        match polarity {
+            InterruptPolarity::ActiveHigh => self.difference(Self::H_LACTIVE),
+            InterruptPolarity::ActiveLow => self.union(Self::H_LACTIVE),
+        }
+    }
+}
+
 register! {
   /// STATUS_REG_A
   #[derive(Debug, Copy, Clone)]
@@ -268,7 +424,7 @@ register! {
 
 register! {
   /// FIFO_CTRL_REG_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct FifoCtrlRegA: 0x2E {
     const FM1  = 0b10000000;
     const FM0  = 0b01000000;
@@ -307,7 +463,7 @@ impl FifoCtrlRegA {
 
 register! {
   /// FIFO_SRC_REG_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct FifoSrcRegA: 0x2F {
     const WTM       = 0b10000000;
     const OVRN_FIFO = 0b01000000;
@@ -317,12 +473,14 @@ register! {
     const FSS2      = 0b00000100;
     const FSS1      = 0b00000010;
     const FSS0      = 0b00000001;
+
+    const FSS = Self::FSS4.bits() | Self::FSS3.bits() | Self::FSS2.bits() | Self::FSS1.bits() | Self::FSS0.bits();
   }
 }
 
 register! {
   /// INT1_CFG_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct Int1CfgA: 0x30 {
     const AOI       = 0b10000000;
     const D6        = 0b01000000;
@@ -341,9 +499,24 @@ register! {
   }
 }
 
+impl Int1CfgA {
+    pub const fn with_mode(self, mode: InertialInterruptMode) -> Self {
+        match mode {
+            InertialInterruptMode::Or => self.difference(Self::AOI).difference(Self::D6),
+            InertialInterruptMode::And => self.union(Self::AOI).difference(Self::D6),
+            InertialInterruptMode::Movement6D | InertialInterruptMode::Movement4D => {
+                self.difference(Self::AOI).union(Self::D6)
+            }
+            InertialInterruptMode::Position6D | InertialInterruptMode::Position4D => {
+                self.union(Self::AOI).union(Self::D6)
+            }
+        }
+    }
+}
+
 register! {
   /// INT1_SRC_A
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct Int1SrcA: 0x31 {
     const IA = 0b01000000;
     const ZH = 0b00100000;
@@ -355,6 +528,167 @@ register! {
   }
 }
 
+register! {
+  /// INT1_THS_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct Int1ThsA: 0x32 {
+    const THS = 0b01111111;
+  }
+}
+
+register! {
+  /// INT1_DURATION_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct Int1DurationA: 0x33 {
+    const D = 0b01111111;
+  }
+}
+
+register! {
+  /// INT2_CFG_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct Int2CfgA: 0x34 {
+    const AOI       = 0b10000000;
+    const D6        = 0b01000000;
+    const ZHIE      = 0b00100000;
+    const ZLIE      = 0b00010000;
+    const YHIE      = 0b00001000;
+    const YLIE      = 0b00000100;
+    const XHIE      = 0b00000010;
+    const XLIE      = 0b00000001;
+  }
+}
+
+register! {
+  /// INT2_SRC_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct Int2SrcA: 0x35 {
+    const IA = 0b01000000;
+    const ZH = 0b00100000;
+    const ZL = 0b00010000;
+    const YH = 0b00001000;
+    const YL = 0b00000100;
+    const XH = 0b00000010;
+    const XL = 0b00000001;
+  }
+}
+
+register! {
+  /// INT2_THS_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct Int2ThsA: 0x36 {
+    const THS = 0b01111111;
+  }
+}
+
+register! {
+  /// INT2_DURATION_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct Int2DurationA: 0x37 {
+    const D = 0b01111111;
+  }
+}
+
+register! {
+  /// CLICK_CFG_A
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
+  pub struct ClickCfgA: 0x38 {
+    const ZD = 0b00100000;
+    const ZS = 0b00010000;
+    const YD = 0b00001000;
+    const YS = 0b00000100;
+    const XD = 0b00000010;
+    const XS = 0b00000001;
+  }
+}
+
+register! {
+  /// CLICK_SRC_A
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
+  pub struct ClickSrcA: 0x39 {
+    const IA     = 0b01000000;
+    const DCLICK = 0b00100000;
+    const SCLICK = 0b00010000;
+    const SIGN   = 0b00001000;
+    const Z      = 0b00000100;
+    const Y      = 0b00000010;
+    const X      = 0b00000001;
+  }
+}
+
+register! {
+  /// CLICK_THS_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct ClickThsA: 0x3A {
+    const LIR_CLICK = 0b10000000;
+    const THS       = 0b01111111;
+  }
+}
+
+register! {
+  /// TIME_LIMIT_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct TimeLimitA: 0x3B {
+    const TLI = 0b01111111;
+  }
+}
+
+register! {
+  /// TIME_LATENCY_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct TimeLatencyA: 0x3C {
+    const TLA = 0b11111111;
+  }
+}
+
+register! {
+  /// TIME_WINDOW_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct TimeWindowA: 0x3D {
+    const TW = 0b11111111;
+  }
+}
+
+register! {
+  /// ACT_THS_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct ActThsA: 0x3E {
+    const ACTH = 0b01111111;
+  }
+}
+
+register! {
+  /// ACT_DUR_A
+  #[derive(Debug, Default, Copy, Clone)]
+  pub struct ActDurA: 0x3F {
+    const ACTD = 0b11111111;
+  }
+}
+
+/// OFFSET_X_REG_L_M to OFFSET_Z_REG_H_M
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct OffsetRegM {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
+impl RegRead<(u16, u16, u16)> for OffsetRegM {
+    type Output = MagneticField;
+
+    const ADDR: u8 = 0x45;
+
+    fn from_data((x, y, z): (u16, u16, u16)) -> Self::Output {
+        MagneticField { x, y, z }
+    }
+}
+
+impl RegWrite<(u16, u16, u16)> for OffsetRegM {
+    fn data(&self) -> (u16, u16, u16) {
+        (self.x as u16, self.y as u16, self.z as u16)
+    }
+}
+
 register! {
   /// WHO_AM_I_A_M
   pub type WhoAmIM: 0x4F = MagnetometerId;
@@ -366,7 +700,7 @@ impl WhoAmIM {
 
 register! {
   /// CFG_REG_A_M
-  #[derive(Debug, Copy, Clone)]
+  #[derive(Debug, Copy, Clone, PartialEq)]
   pub struct CfgRegAM: 0x60 {
     const COMP_TEMP_EN = 0b10000000;
     const REBOOT       = 0b01000000;
@@ -408,6 +742,10 @@ impl CfgRegAM {
         self.difference(Self::MD1).difference(Self::MD0) // 0b00
     }
 
+    pub const fn is_continuous_mode(&self) -> bool {
+        !self.intersects(Self::MD) // 0b00
+    }
+
     pub const fn is_single_mode(&self) -> bool {
         !self.contains(CfgRegAM::MD1) && self.contains(CfgRegAM::MD0)
     }
@@ -416,7 +754,6 @@ impl CfgRegAM {
         self.difference(CfgRegAM::MD1).union(CfgRegAM::MD0) // 0b01
     }
 
-    #[cfg(test)]
     pub const fn is_idle_mode(&self) -> bool {
         self.contains(CfgRegAM::MD1) // 0b10 or 0b11
     }
@@ -456,7 +793,7 @@ impl CfgRegAM {
 
 register! {
   /// CFG_REG_B_M
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct CfgRegBM: 0x61 {
     const OFF_CANC_ONE_SHOT = 0b00010000;
     const INT_ON_DATA_OFF   = 0b00001000;
@@ -470,11 +807,26 @@ impl CfgRegBM {
     pub const fn offset_cancellation(&self) -> bool {
         self.contains(CfgRegBM::OFF_CANC)
     }
+
+    pub const fn set_pulse_frequency(&self) -> MagSetPulseFrequency {
+        if self.contains(Self::SET_FREQ) {
+            MagSetPulseFrequency::PowerOn
+        } else {
+            MagSetPulseFrequency::Every63Odr
+        }
+    }
+
+    pub const fn with_set_pulse_frequency(self, frequency: MagSetPulseFrequency) -> Self {
+        match frequency {
+            MagSetPulseFrequency::Every63Odr => self.difference(Self::SET_FREQ),
+            MagSetPulseFrequency::PowerOn => self.union(Self::SET_FREQ),
+        }
+    }
 }
 
 register! {
   /// CFG_REG_C_M
-  #[derive(Debug, Default, Copy, Clone)]
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
   pub struct CfgRegCM: 0x62 {
     const INT_MAG_PIN = 0b01000000;
     const I2C_DIS     = 0b00100000;
@@ -485,6 +837,96 @@ register! {
   }
 }
 
+impl CfgRegCM {
+    pub const fn endianness(&self) -> Endianness {
+        if self.contains(Self::BLE) {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+
+    pub const fn with_endianness(self, endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::Little => self.difference(Self::BLE),
+            Endianness::Big => self.union(Self::BLE),
+        }
+    }
+}
+
+register! {
+  /// INT_CTRL_REG_M
+  #[derive(Debug, Copy, Clone, PartialEq)]
+  pub struct IntCtrlRegM: 0x63 {
+    const XIEN = 0b10000000;
+    const YIEN = 0b01000000;
+    const ZIEN = 0b00100000;
+    const IEA  = 0b00000100;
+    const IEL  = 0b00000010;
+    const IEN  = 0b00000001;
+
+    const AXES = Self::XIEN.bits() | Self::YIEN.bits() | Self::ZIEN.bits();
+  }
+}
+
+impl Default for IntCtrlRegM {
+    fn default() -> Self {
+        Self::AXES
+    }
+}
+
+impl IntCtrlRegM {
+    pub const fn polarity(&self) -> InterruptPolarity {
+        if self.contains(Self::IEA) {
+            InterruptPolarity::ActiveHigh
+        } else {
+            InterruptPolarity::ActiveLow
+        }
+    }
+
+    pub const fn with_polarity(self, polarity: InterruptPolarity) -> Self {
+        match polarity {
+            InterruptPolarity::ActiveHigh => self.union(Self::IEA),
+            InterruptPolarity::ActiveLow => self.difference(Self::IEA),
+        }
+    }
+}
+
+register! {
+  /// INT_SOURCE_REG_M
+  #[derive(Debug, Default, Copy, Clone, PartialEq)]
+  pub struct IntSourceRegM: 0x64 {
+    const P_TH_S_X = 0b10000000;
+    const P_TH_S_Y = 0b01000000;
+    const P_TH_S_Z = 0b00100000;
+    const N_TH_S_X = 0b00010000;
+    const N_TH_S_Y = 0b00001000;
+    const N_TH_S_Z = 0b00000100;
+    const MROI     = 0b00000010;
+    const INT      = 0b00000001;
+  }
+}
+
+/// INT_THS_L_REG_M and INT_THS_H_REG_M
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct IntThsRegM(pub u16);
+
+impl RegRead<u16> for IntThsRegM {
+    type Output = Self;
+
+    const ADDR: u8 = 0x65;
+
+    fn from_data(data: u16) -> Self::Output {
+        Self(data)
+    }
+}
+
+impl RegWrite<u16> for IntThsRegM {
+    fn data(&self) -> u16 {
+        self.0
+    }
+}
+
 register! {
   /// STATUS_REG_M
   pub type StatusRegM: 0x67 = StatusFlags;
@@ -493,6 +935,7 @@ register! {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::InertialInterruptConfig;
 
     #[test]
     fn ctrl_reg_1_a() {
@@ -518,6 +961,62 @@ mod tests {
         check_odr(AccelOutputDataRate::Hz400, 0b0111);
     }
 
+    #[test]
+    fn ctrl_reg_1_a_odr() {
+        use AccelOutputDataRate::*;
+
+        for odr in [
+            Hz1,
+            Hz10,
+            Hz25,
+            Hz50,
+            Hz100,
+            Hz200,
+            Hz400,
+            Khz1_344,
+            Khz1_620LowPower,
+            Khz5_376LowPower,
+        ] {
+            assert_eq!(CtrlReg1A::default().with_odr(odr).odr(), Some(odr));
+        }
+
+        assert_eq!(CtrlReg1A::default().odr(), None);
+        assert_eq!(CtrlReg1A::ODR3.odr(), None);
+    }
+
+    #[test]
+    fn ctrl_reg_2_a() {
+        let ctrl = CtrlReg2A::from_high_pass_config(&AccelHighPassConfig::default());
+        assert_eq!(ctrl.bits(), 0, "Filter bypassed by default.");
+
+        let check_mode = |mode, value| {
+            assert_eq!(
+                ctrl.with_mode(mode).intersection(CtrlReg2A::HPM).bits() >> 6,
+                value
+            );
+        };
+
+        check_mode(AccelHighPassMode::NormalWithReset, 0b00);
+        check_mode(AccelHighPassMode::Reference, 0b01);
+        check_mode(AccelHighPassMode::Normal, 0b10);
+        check_mode(AccelHighPassMode::AutoResetOnInterrupt, 0b11);
+
+        let check_cutoff = |cutoff, value| {
+            assert_eq!(
+                ctrl.with_cutoff(cutoff)
+                    .intersection(CtrlReg2A::HPCF)
+                    .bits()
+                    >> 4,
+                value
+            );
+        };
+
+        check_cutoff(AccelHighPassCutoff::Highest, 0b00);
+        check_cutoff(AccelHighPassCutoff::High, 0b01);
+        check_cutoff(AccelHighPassCutoff::Low, 0b10);
+        check_cutoff(AccelHighPassCutoff::Lowest, 0b11);
+    }
+
     #[test]
     fn ctrl_reg_3_a() {
         let ctrl = CtrlReg3A::default();
@@ -542,6 +1041,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ctrl_reg_6_a() {
+        let ctrl = CtrlReg6A::default();
+        assert_eq!(ctrl.polarity(), InterruptPolarity::ActiveHigh);
+
+        let ctrl = ctrl.with_polarity(InterruptPolarity::ActiveLow);
+        assert_eq!(ctrl.polarity(), InterruptPolarity::ActiveLow);
+        assert_eq!(ctrl.bits(), 0b00000010);
+
+        let ctrl = CtrlReg6A::default();
+        let ctrl_all = CtrlReg6A::from_bits_truncate(0b11100000);
+
+        let mut bits = 0b10000000;
+        for interrupt in [Interrupt::Click, Interrupt::Aoi1, Interrupt::Aoi2] {
+            assert_eq!(ctrl.with_interrupt(interrupt).unwrap().bits(), bits);
+            assert_eq!(
+                ctrl_all.without_interrupt(interrupt).unwrap().bits(),
+                (!bits) & 0b11100000
+            );
+            bits >>= 1;
+        }
+
+        for interrupt in [
+            Interrupt::DataReady1,
+            Interrupt::DataReady2,
+            Interrupt::FifoWatermark,
+            Interrupt::FifoOverrun,
+        ] {
+            assert!(ctrl.with_interrupt(interrupt).is_none());
+            assert!(ctrl.without_interrupt(interrupt).is_none());
+        }
+    }
+
+    #[test]
+    fn int1_cfg_a() {
+        let config = InertialInterruptConfig::new()
+            .x_high(true)
+            .y_low(true)
+            .z_high(true)
+            .z_high(false);
+        assert_eq!(config.events, Int1CfgA::XHIE | Int1CfgA::YLIE);
+
+        let check_mode = |mode, value| {
+            assert_eq!(config.events.with_mode(mode).bits() >> 6, value);
+        };
+
+        check_mode(InertialInterruptMode::Or, 0b00);
+        check_mode(InertialInterruptMode::Movement6D, 0b01);
+        check_mode(InertialInterruptMode::And, 0b10);
+        check_mode(InertialInterruptMode::Position6D, 0b11);
+        check_mode(InertialInterruptMode::Movement4D, 0b01);
+        check_mode(InertialInterruptMode::Position4D, 0b11);
+    }
+
     #[test]
     fn ctrl_reg_4_a() {
         let ctrl = CtrlReg4A::default();
@@ -583,4 +1136,21 @@ mod tests {
         assert!(!cfg.is_single_mode());
         assert!(!cfg.is_idle_mode());
     }
+
+    #[test]
+    fn cfg_reg_b_m() {
+        let cfg = CfgRegBM::default();
+        assert_eq!(
+            cfg.set_pulse_frequency(),
+            MagSetPulseFrequency::Every63Odr,
+            "Set pulse every 63 ODR is default."
+        );
+
+        let cfg = cfg.with_set_pulse_frequency(MagSetPulseFrequency::PowerOn);
+        assert_eq!(cfg, CfgRegBM::SET_FREQ);
+        assert_eq!(cfg.set_pulse_frequency(), MagSetPulseFrequency::PowerOn);
+
+        let cfg = cfg.with_set_pulse_frequency(MagSetPulseFrequency::Every63Odr);
+        assert_eq!(cfg, CfgRegBM::empty());
+    }
 }
@@ -0,0 +1,170 @@
+use core::fmt;
+
+use bitflags::Flags;
+use maybe_async_cfg::maybe;
+
+use crate::{
+    interface::{ReadData, WriteData},
+    register_address::{
+        CfgRegAM, CfgRegBM, CfgRegCM, ClickCfgA, ClickSrcA, ClickThsA, CtrlReg1A, CtrlReg2A,
+        CtrlReg3A, CtrlReg4A, CtrlReg5A, CtrlReg6A, FifoCtrlRegA, FifoSrcRegA, Int1CfgA, Int1SrcA,
+        Int2CfgA, Int2SrcA, IntCtrlRegM, IntSourceRegM, StatusRegAuxA, TempCfgRegA,
+    },
+    types::StatusFlags,
+    Error, Lsm303agr, RegisterDump,
+};
+
+/// First accelerometer output register (`OUT_X_L_A`).
+const ACCEL_OUT_FIRST: u8 = 0x28;
+/// Number of accelerometer output registers (`OUT_X_L_A` to `OUT_Z_H_A`).
+const ACCEL_OUT_LEN: usize = 6;
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE, MODE> Lsm303agr<DI, MODE>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    /// Read all accelerometer and magnetometer registers.
+    ///
+    /// The magnetometer registers are read in one burst read. The accelerometer
+    /// output registers are read in a separate burst read, since the address
+    /// pointer rolls back to `OUT_X_L_A` after `OUT_Z_H_A` when the FIFO is enabled.
+    /// Note that this reads the interrupt source registers, which clears
+    /// latched interrupts, and the output registers, which removes a sample
+    /// from the accelerometer FIFO if it is enabled.
+    pub async fn dump_registers(&mut self) -> Result<RegisterDump, Error<CommE>> {
+        let mut dump = RegisterDump {
+            accel: [0; RegisterDump::ACCEL_LEN],
+            mag: [0; RegisterDump::MAG_LEN],
+        };
+        let (before, rest) = dump
+            .accel
+            .split_at_mut((ACCEL_OUT_FIRST - RegisterDump::ACCEL_FIRST) as usize);
+        let (output, after) = rest.split_at_mut(ACCEL_OUT_LEN);
+        self.iface
+            .read_accel_registers(RegisterDump::ACCEL_FIRST, before)
+            .await?;
+        self.iface
+            .read_accel_registers(ACCEL_OUT_FIRST, output)
+            .await?;
+        self.iface
+            .read_accel_registers(ACCEL_OUT_FIRST + ACCEL_OUT_LEN as u8, after)
+            .await?;
+        self.iface
+            .read_mag_registers(RegisterDump::MAG_FIRST, &mut dump.mag)
+            .await?;
+        Ok(dump)
+    }
+}
+
+type Decoder = fn(&mut fmt::Formatter<'_>, u8) -> fmt::Result;
+
+/// Documented accelerometer registers
+const ACCEL_REGISTERS: &[(u8, &str, Option<Decoder>)] = &[
+    (0x07, "STATUS_REG_AUX_A", Some(write_flags::<StatusRegAuxA>)),
+    (0x0C, "OUT_TEMP_L_A", None),
+    (0x0D, "OUT_TEMP_H_A", None),
+    (0x0F, "WHO_AM_I_A", None),
+    (0x1F, "TEMP_CFG_REG_A", Some(write_flags::<TempCfgRegA>)),
+    (0x20, "CTRL_REG1_A", Some(write_flags::<CtrlReg1A>)),
+    (0x21, "CTRL_REG2_A", Some(write_flags::<CtrlReg2A>)),
+    (0x22, "CTRL_REG3_A", Some(write_flags::<CtrlReg3A>)),
+    (0x23, "CTRL_REG4_A", Some(write_flags::<CtrlReg4A>)),
+    (0x24, "CTRL_REG5_A", Some(write_flags::<CtrlReg5A>)),
+    (0x25, "CTRL_REG6_A", Some(write_flags::<CtrlReg6A>)),
+    (0x26, "REFERENCE_A", None),
+    (0x27, "STATUS_REG_A", Some(write_flags::<StatusFlags>)),
+    (0x28, "OUT_X_L_A", None),
+    (0x29, "OUT_X_H_A", None),
+    (0x2A, "OUT_Y_L_A", None),
+    (0x2B, "OUT_Y_H_A", None),
+    (0x2C, "OUT_Z_L_A", None),
+    (0x2D, "OUT_Z_H_A", None),
+    (0x2E, "FIFO_CTRL_REG_A", Some(write_flags::<FifoCtrlRegA>)),
+    (0x2F, "FIFO_SRC_REG_A", Some(write_flags::<FifoSrcRegA>)),
+    (0x30, "INT1_CFG_A", Some(write_flags::<Int1CfgA>)),
+    (0x31, "INT1_SRC_A", Some(write_flags::<Int1SrcA>)),
+    (0x32, "INT1_THS_A", None),
+    (0x33, "INT1_DURATION_A", None),
+    (0x34, "INT2_CFG_A", Some(write_flags::<Int2CfgA>)),
+    (0x35, "INT2_SRC_A", Some(write_flags::<Int2SrcA>)),
+    (0x36, "INT2_THS_A", None),
+    (0x37, "INT2_DURATION_A", None),
+    (0x38, "CLICK_CFG_A", Some(write_flags::<ClickCfgA>)),
+    (0x39, "CLICK_SRC_A", Some(write_flags::<ClickSrcA>)),
+    (0x3A, "CLICK_THS_A", Some(write_flags::<ClickThsA>)),
+    (0x3B, "TIME_LIMIT_A", None),
+    (0x3C, "TIME_LATENCY_A", None),
+    (0x3D, "TIME_WINDOW_A", None),
+    (0x3E, "ACT_THS_A", None),
+    (0x3F, "ACT_DUR_A", None),
+];
+
+/// Documented magnetometer registers
+const MAG_REGISTERS: &[(u8, &str, Option<Decoder>)] = &[
+    (0x45, "OFFSET_X_REG_L_M", None),
+    (0x46, "OFFSET_X_REG_H_M", None),
+    (0x47, "OFFSET_Y_REG_L_M", None),
+    (0x48, "OFFSET_Y_REG_H_M", None),
+    (0x49, "OFFSET_Z_REG_L_M", None),
+    (0x4A, "OFFSET_Z_REG_H_M", None),
+    (0x4F, "WHO_AM_I_M", None),
+    (0x60, "CFG_REG_A_M", Some(write_flags::<CfgRegAM>)),
+    (0x61, "CFG_REG_B_M", Some(write_flags::<CfgRegBM>)),
+    (0x62, "CFG_REG_C_M", Some(write_flags::<CfgRegCM>)),
+    (0x63, "INT_CTRL_REG_M", Some(write_flags::<IntCtrlRegM>)),
+    (0x64, "INT_SOURCE_REG_M", Some(write_flags::<IntSourceRegM>)),
+    (0x65, "INT_THS_L_REG_M", None),
+    (0x66, "INT_THS_H_REG_M", None),
+    (0x67, "STATUS_REG_M", Some(write_flags::<StatusFlags>)),
+    (0x68, "OUTX_L_REG_M", None),
+    (0x69, "OUTX_H_REG_M", None),
+    (0x6A, "OUTY_L_REG_M", None),
+    (0x6B, "OUTY_H_REG_M", None),
+    (0x6C, "OUTZ_L_REG_M", None),
+    (0x6D, "OUTZ_H_REG_M", None),
+];
+
+/// Write the names of the single-bit flags set in `value`.
+fn write_flags<F: Flags<Bits = u8>>(f: &mut fmt::Formatter<'_>, value: u8) -> fmt::Result {
+    let mut written = 0;
+    for flag in F::FLAGS {
+        let bits = flag.value().bits();
+        // Skip multi-bit fields and aliases of already written bits.
+        if bits.count_ones() != 1 || value & bits == 0 || written & bits != 0 {
+            continue;
+        }
+        f.write_str(if written == 0 { " " } else { " | " })?;
+        f.write_str(flag.name())?;
+        written |= bits;
+    }
+    Ok(())
+}
+
+fn write_registers(
+    f: &mut fmt::Formatter<'_>,
+    registers: &[(u8, &str, Option<Decoder>)],
+    value: impl Fn(u8) -> Option<u8>,
+) -> fmt::Result {
+    for (address, name, decoder) in registers {
+        if let Some(value) = value(*address) {
+            write!(f, "\n  {address:#04x} {name:<16} {value:#04x}")?;
+            if let Some(decoder) = decoder {
+                decoder(f, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Accelerometer:")?;
+        write_registers(f, ACCEL_REGISTERS, |address| self.accel_register(address))?;
+        f.write_str("\nMagnetometer:")?;
+        write_registers(f, MAG_REGISTERS, |address| self.mag_register(address))
+    }
+}
@@ -0,0 +1,141 @@
+use maybe_async_cfg::maybe;
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::delay::DelayNs;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs;
+
+use crate::{
+    interface::{ReadData, WriteData},
+    mode,
+    register_address::{
+        ActDurA, ActThsA, CfgRegAM, CfgRegBM, CfgRegCM, ClickCfgA, ClickThsA, CtrlReg1A, CtrlReg2A,
+        CtrlReg3A, CtrlReg4A, CtrlReg5A, CtrlReg6A, FifoCtrlRegA, Int1CfgA, Int1DurationA,
+        Int1ThsA, Int2CfgA, Int2DurationA, Int2ThsA, IntCtrlRegM, ReferenceA, TempCfgRegA,
+        TimeLatencyA, TimeLimitA, TimeWindowA,
+    },
+    Error, Lsm303agr, ModeChangeError,
+};
+
+/// Accelerometer boot time in ms.
+const ACCEL_BOOT_MS: u32 = 5;
+
+/// Magnetometer soft reset time in ms.
+const MAG_SOFT_RESET_MS: u32 = 1;
+
+/// Magnetometer boot time in ms.
+const MAG_BOOT_MS: u32 = 20;
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE, MODE> Lsm303agr<DI, MODE>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    /// Reboot the accelerometer and reset its configuration.
+    ///
+    /// The accelerometer memory content is reloaded and all accelerometer
+    /// control, interrupt, click and activity registers are set to their
+    /// default values, which powers down the accelerometer.
+    pub async fn reset_accel<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<CommE>> {
+        self.iface.write_accel_register(CtrlReg5A::BOOT).await?;
+        delay.delay_ms(ACCEL_BOOT_MS).await;
+
+        // Rebooting does not change the control registers.
+        self.ctrl_reg1_a = CtrlReg1A::default();
+        self.iface.write_accel_register(self.ctrl_reg1_a).await?;
+        self.accel_odr = None;
+        self.ctrl_reg2_a = CtrlReg2A::default();
+        self.iface.write_accel_register(self.ctrl_reg2_a).await?;
+        self.ctrl_reg3_a = CtrlReg3A::default();
+        self.iface.write_accel_register(self.ctrl_reg3_a).await?;
+        // Keep the SPI mode, otherwise the communication would be interrupted.
+        self.ctrl_reg4_a =
+            CtrlReg4A::default().union(self.ctrl_reg4_a.intersection(CtrlReg4A::SPI_ENABLE));
+        self.iface.write_accel_register(self.ctrl_reg4_a).await?;
+        self.ctrl_reg5_a = CtrlReg5A::default();
+        self.iface.write_accel_register(self.ctrl_reg5_a).await?;
+        self.ctrl_reg6_a = CtrlReg6A::default();
+        self.iface.write_accel_register(self.ctrl_reg6_a).await?;
+        self.temp_cfg_reg_a = TempCfgRegA::default();
+        self.iface.write_accel_register(self.temp_cfg_reg_a).await?;
+        self.fifo_ctrl_reg_a = FifoCtrlRegA::default();
+        self.iface
+            .write_accel_register(self.fifo_ctrl_reg_a)
+            .await?;
+
+        self.iface.write_accel_register(ReferenceA::empty()).await?;
+        self.iface.write_accel_register(Int1CfgA::empty()).await?;
+        self.iface.write_accel_register(Int1ThsA::empty()).await?;
+        self.iface
+            .write_accel_register(Int1DurationA::empty())
+            .await?;
+        self.iface.write_accel_register(Int2CfgA::empty()).await?;
+        self.iface.write_accel_register(Int2ThsA::empty()).await?;
+        self.iface
+            .write_accel_register(Int2DurationA::empty())
+            .await?;
+        self.iface.write_accel_register(ClickCfgA::empty()).await?;
+        self.iface.write_accel_register(ClickThsA::empty()).await?;
+        self.iface.write_accel_register(TimeLimitA::empty()).await?;
+        self.iface
+            .write_accel_register(TimeLatencyA::empty())
+            .await?;
+        self.iface
+            .write_accel_register(TimeWindowA::empty())
+            .await?;
+        self.iface.write_accel_register(ActThsA::empty()).await?;
+        self.iface.write_accel_register(ActDurA::empty()).await?;
+
+        Ok(())
+    }
+
+    /// Reset the magnetometer configuration and reboot it.
+    ///
+    /// All magnetometer registers are set to their default values, which puts
+    /// the magnetometer in idle mode.
+    pub async fn reset_mag<D: DelayNs>(
+        mut self,
+        delay: &mut D,
+    ) -> Result<Lsm303agr<DI, mode::MagOneShot>, ModeChangeError<CommE, Self>> {
+        match self.reset_mag_inner(delay).await {
+            Err(error) => Err(ModeChangeError { error, dev: self }),
+            Ok(_) => Ok(self.with_mag_mode()),
+        }
+    }
+
+    /// Reset both the accelerometer and the magnetometer.
+    ///
+    /// See [`reset_accel()`](Lsm303agr::reset_accel) and [`reset_mag()`](Lsm303agr::reset_mag).
+    pub async fn reset_all<D: DelayNs>(
+        mut self,
+        delay: &mut D,
+    ) -> Result<Lsm303agr<DI, mode::MagOneShot>, ModeChangeError<CommE, Self>> {
+        if let Err(error) = self.reset_accel(delay).await {
+            return Err(ModeChangeError { error, dev: self });
+        }
+
+        self.reset_mag(delay).await
+    }
+
+    async fn reset_mag_inner<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<CommE>> {
+        self.iface
+            .write_mag_register(CfgRegAM::default().union(CfgRegAM::SOFT_RST))
+            .await?;
+        delay.delay_ms(MAG_SOFT_RESET_MS).await;
+
+        self.iface
+            .write_mag_register(CfgRegAM::default().union(CfgRegAM::REBOOT))
+            .await?;
+        delay.delay_ms(MAG_BOOT_MS).await;
+
+        self.cfg_reg_a_m = CfgRegAM::default();
+        self.cfg_reg_b_m = CfgRegBM::default();
+        self.cfg_reg_c_m = CfgRegCM::default();
+        self.int_ctrl_reg_m = IntCtrlRegM::default();
+
+        Ok(())
+    }
+}
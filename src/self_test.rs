@@ -0,0 +1,299 @@
+use maybe_async_cfg::maybe;
+
+#[cfg(not(feature = "async"))]
+use embedded_hal::delay::DelayNs;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs;
+
+use crate::{
+    interface::{ReadData, WriteData},
+    register_address::{CfgRegAM, CfgRegBM, CfgRegCM, CtrlReg1A, CtrlReg2A, CtrlReg4A},
+    AccelMode, AccelOutputDataRate, Error, Lsm303agr, MagOutputDataRate, MagneticField,
+    SelfTestReport,
+};
+
+/// Number of samples averaged in each accelerometer self-test phase.
+const ACCEL_SAMPLES: i32 = 5;
+
+/// Accelerometer self-test limits in m*g* (normal mode, ±2 *g*).
+const ACCEL_LIMITS_MG: (i32, i32) = (68, 1440);
+
+/// Accelerometer settling time after a configuration change in ms.
+const ACCEL_SETTLE_MS: u32 = 90;
+
+/// Number of samples averaged in each magnetometer self-test phase.
+const MAG_SAMPLES: i32 = 50;
+
+/// Maximum number of 1 ms waits for a new sample during the self-tests.
+const DATA_TIMEOUT_MS: u32 = 100;
+
+/// Magnetometer self-test limits in mG (milli-Gauss).
+const MAG_LIMITS_MG: (i32, i32) = (15, 500);
+
+/// Magnetometer settling time after the configuration in ms.
+const MAG_SETTLE_MS: u32 = 20;
+
+/// Magnetometer settling time after enabling the self-test in ms.
+const MAG_SELF_TEST_SETTLE_MS: u32 = 60;
+
+#[maybe(
+    sync(cfg(not(feature = "async")), keep_self,),
+    async(cfg(feature = "async"), keep_self,)
+)]
+impl<DI, CommE, MODE> Lsm303agr<DI, MODE>
+where
+    DI: ReadData<Error = Error<CommE>> + WriteData<Error = Error<CommE>>,
+{
+    /// Run the accelerometer self-test.
+    ///
+    /// This follows the procedure described in the [datasheet]: the accelerometer
+    /// is configured in normal mode at 100 Hz with a ±2 *g* scale, then the
+    /// average of 5 samples is taken with the self-test disabled and with
+    /// the self-test 0 enabled. The absolute difference on each axis must be
+    /// within \[68, 1440\] m*g*.
+    ///
+    /// The device must be kept still during the test. The high-pass filter is
+    /// bypassed during the test.
+    ///
+    /// If the accelerometer does not provide new data within 100 ms, a failed
+    /// report with zero differences is returned.
+    ///
+    /// The previous accelerometer mode, output data rate, scale and high-pass
+    /// filter configuration are restored afterwards, waiting for the
+    /// accelerometer to change back into the previous mode.
+    ///
+    /// [datasheet]: https://www.st.com/resource/en/datasheet/lsm303agr.pdf
+    pub async fn accel_self_test<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<SelfTestReport, Error<CommE>> {
+        let reg1 = self.ctrl_reg1_a;
+        let reg2 = self.ctrl_reg2_a;
+        let reg4 = self.ctrl_reg4_a;
+        let odr = self.accel_odr;
+
+        let result = self.accel_self_test_inner(delay).await;
+
+        self.iface.write_accel_register(reg4).await?;
+        self.ctrl_reg4_a = reg4;
+        self.iface.write_accel_register(reg1).await?;
+        self.ctrl_reg1_a = reg1;
+        self.accel_odr = odr;
+        self.iface.write_accel_register(reg2).await?;
+        self.ctrl_reg2_a = reg2;
+
+        if let Some(odr) = odr {
+            // The self-test runs in normal mode.
+            let change_time = AccelMode::Normal.change_time_us(self.get_accel_mode().await, odr);
+            delay.delay_us(change_time).await;
+        }
+
+        result
+    }
+
+    async fn accel_self_test_inner<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<SelfTestReport, Error<CommE>> {
+        // The high-pass filter would remove the static self-test offset.
+        let reg2 = CtrlReg2A::default();
+        self.iface.write_accel_register(reg2).await?;
+        self.ctrl_reg2_a = reg2;
+
+        let reg4 = self
+            .ctrl_reg4_a
+            .intersection(CtrlReg4A::BLE.union(CtrlReg4A::SPI_ENABLE))
+            .union(CtrlReg4A::BDU);
+        self.iface.write_accel_register(reg4).await?;
+        self.ctrl_reg4_a = reg4;
+
+        let reg1 = CtrlReg1A::default().with_odr(AccelOutputDataRate::Hz100);
+        self.iface.write_accel_register(reg1).await?;
+        self.ctrl_reg1_a = reg1;
+        self.accel_odr = Some(AccelOutputDataRate::Hz100);
+
+        delay.delay_ms(ACCEL_SETTLE_MS).await;
+        let (x_nost, y_nost, z_nost) = match self.accel_average_mg(delay).await? {
+            Some(average) => average,
+            None => return Ok(SelfTestReport::new((0, 0, 0), ACCEL_LIMITS_MG)),
+        };
+
+        let reg4 = reg4.union(CtrlReg4A::ST0);
+        self.iface.write_accel_register(reg4).await?;
+        self.ctrl_reg4_a = reg4;
+
+        delay.delay_ms(ACCEL_SETTLE_MS).await;
+        let (x_st, y_st, z_st) = match self.accel_average_mg(delay).await? {
+            Some(average) => average,
+            None => return Ok(SelfTestReport::new((0, 0, 0), ACCEL_LIMITS_MG)),
+        };
+
+        Ok(SelfTestReport::new(
+            (
+                (x_st - x_nost).abs(),
+                (y_st - y_nost).abs(),
+                (z_st - z_nost).abs(),
+            ),
+            ACCEL_LIMITS_MG,
+        ))
+    }
+
+    /// Discard the first sample and average the following ones.
+    ///
+    /// Returns `None` if no new data is available in time.
+    async fn accel_average_mg<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Option<(i32, i32, i32)>, Error<CommE>> {
+        let (mut x, mut y, mut z) = (0, 0, 0);
+        for i in 0..=ACCEL_SAMPLES {
+            let mut retries = 0;
+            while !self.accel_status().await?.xyz_new_data() {
+                if retries == DATA_TIMEOUT_MS {
+                    return Ok(None);
+                }
+                retries += 1;
+                delay.delay_ms(1).await;
+            }
+            let (x_mg, y_mg, z_mg) = self.acceleration().await?.xyz_mg();
+            if i > 0 {
+                x += x_mg;
+                y += y_mg;
+                z += z_mg;
+            }
+        }
+
+        Ok(Some((
+            x / ACCEL_SAMPLES,
+            y / ACCEL_SAMPLES,
+            z / ACCEL_SAMPLES,
+        )))
+    }
+
+    /// Run the magnetometer self-test.
+    ///
+    /// This follows the procedure described in the [datasheet]: the magnetometer
+    /// is configured in continuous high-resolution mode at 100 Hz, then the average
+    /// of 50 samples is taken with the self-test disabled and enabled.
+    /// The absolute difference on each axis must be within \[15, 500\] mG.
+    ///
+    /// The device must be kept still and away from magnetic disturbances
+    /// during the test.
+    ///
+    /// If the magnetometer does not provide new data within 100 ms, a failed
+    /// report with zero differences is returned.
+    ///
+    /// The previous magnetometer configuration is restored afterwards, so the
+    /// device stays in the same mode it was in before. If the magnetometer was
+    /// not idle, this waits for it to turn on again.
+    ///
+    /// [datasheet]: https://www.st.com/resource/en/datasheet/lsm303agr.pdf
+    pub async fn mag_self_test<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<SelfTestReport, Error<CommE>> {
+        let rega = self.cfg_reg_a_m;
+        let regb = self.cfg_reg_b_m;
+        let regc = self.cfg_reg_c_m;
+
+        let result = self.mag_self_test_inner(delay).await;
+
+        self.iface.write_mag_register(regc).await?;
+        self.cfg_reg_c_m = regc;
+        self.iface.write_mag_register(regb).await?;
+        self.cfg_reg_b_m = regb;
+        self.iface.write_mag_register(rega).await?;
+        self.cfg_reg_a_m = rega;
+
+        if !rega.is_idle_mode() {
+            delay
+                .delay_us(rega.turn_on_time_us(regb.offset_cancellation()))
+                .await;
+        }
+
+        result
+    }
+
+    async fn mag_self_test_inner<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<SelfTestReport, Error<CommE>> {
+        let rega = CfgRegAM::COMP_TEMP_EN
+            .with_odr(MagOutputDataRate::Hz100)
+            .continuous_mode();
+        self.iface.write_mag_register(rega).await?;
+        self.cfg_reg_a_m = rega;
+
+        let regb = CfgRegBM::OFF_CANC;
+        self.iface.write_mag_register(regb).await?;
+        self.cfg_reg_b_m = regb;
+
+        let regc = self
+            .cfg_reg_c_m
+            .intersection(CfgRegCM::I2C_DIS.union(CfgRegCM::BLE))
+            .union(CfgRegCM::BDU);
+        self.iface.write_mag_register(regc).await?;
+        self.cfg_reg_c_m = regc;
+
+        delay.delay_ms(MAG_SETTLE_MS).await;
+        let (x_nost, y_nost, z_nost) = match self.mag_average_mgauss(delay).await? {
+            Some(average) => average,
+            None => return Ok(SelfTestReport::new((0, 0, 0), MAG_LIMITS_MG)),
+        };
+
+        let regc = regc.union(CfgRegCM::SELF_TEST);
+        self.iface.write_mag_register(regc).await?;
+        self.cfg_reg_c_m = regc;
+
+        delay.delay_ms(MAG_SELF_TEST_SETTLE_MS).await;
+        let (x_st, y_st, z_st) = match self.mag_average_mgauss(delay).await? {
+            Some(average) => average,
+            None => return Ok(SelfTestReport::new((0, 0, 0), MAG_LIMITS_MG)),
+        };
+
+        Ok(SelfTestReport::new(
+            (
+                (x_st - x_nost).abs(),
+                (y_st - y_nost).abs(),
+                (z_st - z_nost).abs(),
+            ),
+            MAG_LIMITS_MG,
+        ))
+    }
+
+    /// Discard the first sample and average the following ones.
+    ///
+    /// Returns `None` if no new data is available in time.
+    async fn mag_average_mgauss<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Option<(i32, i32, i32)>, Error<CommE>> {
+        let (mut x, mut y, mut z) = (0, 0, 0);
+        for i in 0..=MAG_SAMPLES {
+            let mut retries = 0;
+            while !self.mag_status().await?.xyz_new_data() {
+                if retries == DATA_TIMEOUT_MS {
+                    return Ok(None);
+                }
+                retries += 1;
+                delay.delay_ms(1).await;
+            }
+            let (x_nt, y_nt, z_nt) = self
+                .iface
+                .read_mag_3_double_registers_with_endianness::<MagneticField>(
+                    self.cfg_reg_c_m.endianness(),
+                )
+                .await?
+                .xyz_nt();
+            if i > 0 {
+                x += x_nt;
+                y += y_nt;
+                z += z_nt;
+            }
+        }
+
+        // 1 mG = 100 nT
+        let n = MAG_SAMPLES * 100;
+        Ok(Some((x / n, y / n, z / n)))
+    }
+}
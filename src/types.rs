@@ -1,6 +1,13 @@
 use bitflags::bitflags;
 
-use crate::register_address::{RegRead, StatusRegAuxA, WhoAmIA, WhoAmIM};
+use crate::{
+    register_address::{
+        CfgRegAM, CfgRegBM, CfgRegCM, ClickCfgA, ClickSrcA, CtrlReg1A, CtrlReg2A, CtrlReg3A,
+        CtrlReg4A, CtrlReg5A, CtrlReg6A, FifoCtrlRegA, FifoSrcRegA, Int1CfgA, Int1SrcA,
+        IntCtrlRegM, IntSourceRegM, RegRead, StatusRegAuxA, TempCfgRegA, WhoAmIA, WhoAmIM,
+    },
+    Lsm303agr,
+};
 
 /// All possible errors in this crate
 #[derive(Debug)]
@@ -26,6 +33,17 @@ pub struct ModeChangeError<CommE, DEV> {
     pub dev: DEV,
 }
 
+/// Device with the magnetometer mode read from the device.
+///
+/// See [`attach()`](crate::Lsm303agr::attach).
+#[derive(Debug)]
+pub enum MagModeDevice<DI> {
+    /// Magnetometer in one-shot mode
+    OneShot(Lsm303agr<DI, mode::MagOneShot>),
+    /// Magnetometer in continuous mode
+    Continuous(Lsm303agr<DI, mode::MagContinuous>),
+}
+
 /// Device operation modes
 pub mod mode {
     /// Marker type for magnetometer in one-shot (single) mode.
@@ -58,6 +76,27 @@ impl AccelerometerId {
     }
 }
 
+bitflags! {
+    /// Set of accelerometer axes
+    ///
+    /// All axes are enabled by default.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AxisSet: u8 {
+        /// X-axis
+        const X = 0b001;
+        /// Y-axis
+        const Y = 0b010;
+        /// Z-axis
+        const Z = 0b100;
+    }
+}
+
+impl Default for AxisSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
 /// An acceleration measurement.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Acceleration {
@@ -66,6 +105,7 @@ pub struct Acceleration {
     pub(crate) z: u16,
     pub(crate) mode: AccelMode,
     pub(crate) scale: AccelScale,
+    pub(crate) axes: AxisSet,
 }
 
 impl RegRead<(u16, u16, u16)> for Acceleration {
@@ -81,6 +121,17 @@ impl RegRead<(u16, u16, u16)> for Acceleration {
 }
 
 impl Acceleration {
+    /// Standard gravity in m/s²
+    const STANDARD_GRAVITY: f32 = 9.806_65;
+
+    /// Axes enabled when the measurement was taken.
+    ///
+    /// The values of disabled axes are not valid.
+    #[inline]
+    pub const fn axes(&self) -> AxisSet {
+        self.axes
+    }
+
     /// Raw acceleration in X-direction.
     #[inline]
     pub const fn x_raw(&self) -> u16 {
@@ -165,6 +216,54 @@ impl Acceleration {
             (z_unscaled as i32) * scaling_factor,
         )
     }
+
+    /// Acceleration in X-direction in *g*.
+    #[inline]
+    pub fn x_g(&self) -> f32 {
+        self.x_mg() as f32 / 1000.0
+    }
+
+    /// Acceleration in Y-direction in *g*.
+    #[inline]
+    pub fn y_g(&self) -> f32 {
+        self.y_mg() as f32 / 1000.0
+    }
+
+    /// Acceleration in Z-direction in *g*.
+    #[inline]
+    pub fn z_g(&self) -> f32 {
+        self.z_mg() as f32 / 1000.0
+    }
+
+    /// Acceleration in X-, Y- and Z-directions in *g*.
+    #[inline]
+    pub fn xyz_g(&self) -> (f32, f32, f32) {
+        (self.x_g(), self.y_g(), self.z_g())
+    }
+
+    /// Acceleration in X-direction in m/s².
+    #[inline]
+    pub fn x_mps2(&self) -> f32 {
+        self.x_g() * Self::STANDARD_GRAVITY
+    }
+
+    /// Acceleration in Y-direction in m/s².
+    #[inline]
+    pub fn y_mps2(&self) -> f32 {
+        self.y_g() * Self::STANDARD_GRAVITY
+    }
+
+    /// Acceleration in Z-direction in m/s².
+    #[inline]
+    pub fn z_mps2(&self) -> f32 {
+        self.z_g() * Self::STANDARD_GRAVITY
+    }
+
+    /// Acceleration in X-, Y- and Z-directions in m/s².
+    #[inline]
+    pub fn xyz_mps2(&self) -> (f32, f32, f32) {
+        (self.x_mps2(), self.y_mps2(), self.z_mps2())
+    }
 }
 
 /// A Magnetometer ID.
@@ -210,7 +309,25 @@ impl RegRead<(u16, u16, u16)> for MagneticField {
 }
 
 impl MagneticField {
-    const SCALING_FACTOR: i32 = 150;
+    pub(crate) const SCALING_FACTOR: i32 = 150;
+
+    /// Convert nT (nano-Tesla) to raw LSB, rounding to the nearest value.
+    pub(crate) const fn raw_from_nt(nt: i32) -> i16 {
+        let half = Self::SCALING_FACTOR / 2;
+        let raw = if nt < 0 {
+            nt.saturating_sub(half) / Self::SCALING_FACTOR
+        } else {
+            nt.saturating_add(half) / Self::SCALING_FACTOR
+        };
+
+        if raw > i16::MAX as i32 {
+            i16::MAX
+        } else if raw < i16::MIN as i32 {
+            i16::MIN
+        } else {
+            raw as i16
+        }
+    }
 
     /// Raw magnetic field in X-direction.
     #[inline]
@@ -283,6 +400,58 @@ impl MagneticField {
     pub const fn xyz_nt(&self) -> (i32, i32, i32) {
         (self.x_nt(), self.y_nt(), self.z_nt())
     }
+
+    /// Magnetic field in X-direction in µT (micro-Tesla).
+    #[inline]
+    pub fn x_microtesla(&self) -> f32 {
+        self.x_nt() as f32 / 1000.0
+    }
+
+    /// Magnetic field in Y-direction in µT (micro-Tesla).
+    #[inline]
+    pub fn y_microtesla(&self) -> f32 {
+        self.y_nt() as f32 / 1000.0
+    }
+
+    /// Magnetic field in Z-direction in µT (micro-Tesla).
+    #[inline]
+    pub fn z_microtesla(&self) -> f32 {
+        self.z_nt() as f32 / 1000.0
+    }
+
+    /// Magnetic field in X-, Y- and Z-directions in µT (micro-Tesla).
+    #[inline]
+    pub fn xyz_microtesla(&self) -> (f32, f32, f32) {
+        (
+            self.x_microtesla(),
+            self.y_microtesla(),
+            self.z_microtesla(),
+        )
+    }
+
+    /// Magnetic field in X-direction in gauss.
+    #[inline]
+    pub fn x_gauss(&self) -> f32 {
+        self.x_nt() as f32 / 100_000.0
+    }
+
+    /// Magnetic field in Y-direction in gauss.
+    #[inline]
+    pub fn y_gauss(&self) -> f32 {
+        self.y_nt() as f32 / 100_000.0
+    }
+
+    /// Magnetic field in Z-direction in gauss.
+    #[inline]
+    pub fn z_gauss(&self) -> f32 {
+        self.z_nt() as f32 / 100_000.0
+    }
+
+    /// Magnetic field in X-, Y- and Z-directions in gauss.
+    #[inline]
+    pub fn xyz_gauss(&self) -> (f32, f32, f32) {
+        (self.x_gauss(), self.y_gauss(), self.z_gauss())
+    }
 }
 
 /// Accelerometer output data rate
@@ -328,6 +497,33 @@ impl AccelOutputDataRate {
         })
     }
 
+    /// Frequency in Hertz.
+    pub(crate) const fn hertz(&self) -> u32 {
+        match self {
+            Self::Hz1 => 1,
+            Self::Hz10 => 10,
+            Self::Hz25 => 25,
+            Self::Hz50 => 50,
+            Self::Hz100 => 100,
+            Self::Hz200 => 200,
+            Self::Hz400 => 400,
+            Self::Khz1_344 => 1344,
+            Self::Khz1_620LowPower => 1620,
+            Self::Khz5_376LowPower => 5376,
+        }
+    }
+
+    /// Convert a time in milliseconds to a number of samples (1/ODR),
+    /// rounding to the nearest sample and clamping to `max`.
+    pub(crate) const fn samples_from_ms(&self, ms: u16, max: u8) -> u8 {
+        let samples = (ms as u32 * self.hertz() + 500) / 1000;
+        if samples > max as u32 {
+            max
+        } else {
+            samples as u8
+        }
+    }
+
     /// 1/ODR ms
     pub(crate) const fn turn_on_time_us_frac_1(&self) -> u32 {
         match self {
@@ -375,6 +571,22 @@ pub enum AccelMode {
 }
 
 impl AccelMode {
+    pub(crate) const fn from_registers(reg1: CtrlReg1A, reg4: CtrlReg4A) -> Self {
+        let power_down = reg1.intersection(CtrlReg1A::ODR).is_empty();
+        let lp_enabled = reg1.contains(CtrlReg1A::LPEN);
+        let hr_enabled = reg4.contains(CtrlReg4A::HR);
+
+        if power_down {
+            Self::PowerDown
+        } else if hr_enabled {
+            Self::HighResolution
+        } else if lp_enabled {
+            Self::LowPower
+        } else {
+            Self::Normal
+        }
+    }
+
     pub(crate) const fn turn_on_time_us(&self, odr: AccelOutputDataRate) -> u32 {
         match self {
             Self::PowerDown => 0,
@@ -429,6 +641,29 @@ pub enum AccelScale {
     G16 = 16,
 }
 
+impl AccelScale {
+    /// Interrupt threshold resolution in m*g* per LSB.
+    pub(crate) const fn threshold_lsb_mg(&self) -> u16 {
+        match self {
+            Self::G2 => 16,
+            Self::G4 => 32,
+            Self::G8 => 62,
+            Self::G16 => 186,
+        }
+    }
+
+    /// Convert a threshold in m*g* to the 7-bit register value, rounding to the nearest step.
+    pub(crate) const fn threshold_from_mg(&self, mg: u16) -> u8 {
+        let lsb = self.threshold_lsb_mg() as u32;
+        let value = (mg as u32 + lsb / 2) / lsb;
+        if value > 0x7F {
+            0x7F
+        } else {
+            value as u8
+        }
+    }
+}
+
 /// Magnetometer output data rate
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MagOutputDataRate {
@@ -465,21 +700,29 @@ impl MagOutputDataRate {
     }
 }
 
+/// Magnetometer set pulse frequency
+///
+/// Releasing the set pulse less often reduces the power consumption,
+/// at the cost of a lower robustness against strong magnetic fields.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum MagSetPulseFrequency {
+    /// Set pulse released every 63 ODR periods (default)
+    #[default]
+    Every63Odr,
+    /// Set pulse released only at power-on after power-down
+    PowerOn,
+}
+
 /// Magnetometer mode
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum MagMode {
     /// Low-power mode
     LowPower,
     /// High resolution mode
+    #[default]
     HighResolution,
 }
 
-impl Default for MagMode {
-    fn default() -> Self {
-        Self::HighResolution
-    }
-}
-
 impl MagMode {
     pub(crate) const fn turn_on_time_us(&self) -> u32 {
         match self {
@@ -489,6 +732,25 @@ impl MagMode {
     }
 }
 
+/// Output data endianness
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Endianness {
+    /// Least significant byte at the lower address (default)
+    #[default]
+    Little,
+    /// Most significant byte at the lower address
+    Big,
+}
+
+impl Endianness {
+    pub(crate) const fn u16_from_bytes(&self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
+    }
+}
+
 bitflags! {
     #[derive(Debug, Default, Copy, Clone, PartialEq)]
     pub struct StatusFlags: u8 {
@@ -563,6 +825,51 @@ impl Status {
     }
 }
 
+/// Accelerometer FIFO status
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FifoStatus {
+    flags: FifoSrcRegA,
+}
+
+impl FifoStatus {
+    /// FIFO buffer depth in samples.
+    pub(crate) const DEPTH: usize = 32;
+
+    pub(crate) const fn new(flags: FifoSrcRegA) -> Self {
+        Self { flags }
+    }
+
+    /// FIFO content is equal to or higher than the watermark level.
+    #[inline]
+    pub const fn watermark(&self) -> bool {
+        self.flags.contains(FifoSrcRegA::WTM)
+    }
+
+    /// FIFO buffer is full and samples are being overwritten.
+    #[inline]
+    pub const fn overrun(&self) -> bool {
+        self.flags.contains(FifoSrcRegA::OVRN_FIFO)
+    }
+
+    /// FIFO buffer is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.flags.contains(FifoSrcRegA::EMPTY)
+    }
+
+    /// Number of unread samples stored in the FIFO buffer.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else if self.overrun() {
+            Self::DEPTH
+        } else {
+            self.flags.intersection(FifoSrcRegA::FSS).bits() as usize
+        }
+    }
+}
+
 /// Temperature sensor status
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct TemperatureStatus {
@@ -640,21 +947,873 @@ pub enum FifoMode {
     StreamToFifo,
 }
 
+/// Accelerometer high-pass filter mode
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum AccelHighPassMode {
+    /// Normal mode, the filter is reset by reading the `REFERENCE` register.
+    #[default]
+    NormalWithReset,
+    /// The `REFERENCE` register value is used as filter reference.
+    Reference,
+    /// Normal mode
+    Normal,
+    /// The filter is reset automatically on interrupt events.
+    AutoResetOnInterrupt,
+}
+
+/// Accelerometer high-pass filter cut-off frequency
+///
+/// The actual cut-off frequency depends on the output data rate.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum AccelHighPassCutoff {
+    /// Highest cut-off frequency (`HPCF = 0b00`)
+    #[default]
+    Highest,
+    /// High cut-off frequency (`HPCF = 0b01`)
+    High,
+    /// Low cut-off frequency (`HPCF = 0b10`)
+    Low,
+    /// Lowest cut-off frequency (`HPCF = 0b11`)
+    Lowest,
+}
+
+/// Accelerometer high-pass filter configuration
+///
+/// The default configuration bypasses the filter on all data paths.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AccelHighPassConfig {
+    /// Filter mode
+    pub mode: AccelHighPassMode,
+    /// Cut-off frequency
+    pub cutoff: AccelHighPassCutoff,
+    /// Send filtered data to the output registers and FIFO.
+    pub output_data: bool,
+    /// Send filtered data to the click function.
+    pub click: bool,
+    /// Send filtered data to the interrupt generator 1.
+    pub aoi1: bool,
+    /// Send filtered data to the interrupt generator 2.
+    pub aoi2: bool,
+}
+
 /// An interrupt.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Interrupt {
-    /// AOI1 interrupt on INT1 pin.
+    /// AOI1 interrupt (INT1 and INT2 pins).
     Aoi1,
-    /// AOI2 interrupt on INT1 pin.
+    /// AOI2 interrupt (INT1 and INT2 pins).
     Aoi2,
-    /// CLICK interrupt on INT1 pin.
+    /// CLICK interrupt (INT1 and INT2 pins).
     Click,
-    /// DRDY1 interrupt on INT1 pin.
+    /// DRDY1 interrupt (INT1 pin only).
     DataReady1,
-    /// DRDY2 interrupt on INT1 pin.
+    /// DRDY2 interrupt (INT1 pin only).
     DataReady2,
-    /// FIFO overrun interrupt on INT1 pin.
+    /// FIFO overrun interrupt (INT1 pin only).
     FifoOverrun,
-    /// FIFO watermark interrupt on INT1 pin.
+    /// FIFO watermark interrupt (INT1 pin only).
     FifoWatermark,
 }
+
+/// An accelerometer interrupt pin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterruptPin {
+    /// INT1 pin
+    Int1,
+    /// INT2 pin
+    Int2,
+}
+
+/// Interrupt pin polarity
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum InterruptPolarity {
+    /// Active high
+    #[default]
+    ActiveHigh,
+    /// Active low
+    ActiveLow,
+}
+
+/// An accelerometer interrupt generator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterruptGenerator {
+    /// Interrupt generator 1 (AOI1)
+    Aoi1,
+    /// Interrupt generator 2 (AOI2)
+    Aoi2,
+}
+
+/// Combination mode of the inertial interrupt events.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum InertialInterruptMode {
+    /// OR combination of the enabled events
+    #[default]
+    Or,
+    /// AND combination of the enabled events
+    And,
+    /// 6-direction movement recognition
+    Movement6D,
+    /// 6-direction position recognition
+    Position6D,
+    /// 4-direction movement recognition (Z-axis disabled)
+    Movement4D,
+    /// 4-direction position recognition (Z-axis disabled)
+    Position4D,
+}
+
+/// Inertial interrupt generator configuration.
+///
+/// The default configuration has no events enabled, which disables the
+/// interrupt generator.
+///
+/// ```
+/// use lsm303agr::{InertialInterruptConfig, InertialInterruptMode};
+///
+/// // Wake up on movement above 250 mg on any axis for at least 2 samples.
+/// let config = InertialInterruptConfig::new()
+///     .mode(InertialInterruptMode::Or)
+///     .x_high(true)
+///     .y_high(true)
+///     .z_high(true)
+///     .threshold_mg(250)
+///     .duration(2);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct InertialInterruptConfig {
+    pub(crate) mode: InertialInterruptMode,
+    pub(crate) events: Int1CfgA,
+    pub(crate) threshold_mg: u16,
+    pub(crate) duration: u8,
+    pub(crate) latched: bool,
+}
+
+impl InertialInterruptConfig {
+    /// Create a new configuration with no events enabled.
+    pub const fn new() -> Self {
+        Self {
+            mode: InertialInterruptMode::Or,
+            events: Int1CfgA::empty(),
+            threshold_mg: 0,
+            duration: 0,
+            latched: false,
+        }
+    }
+
+    /// Set the event combination mode.
+    pub const fn mode(mut self, mode: InertialInterruptMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    const fn with_event(mut self, event: Int1CfgA, enable: bool) -> Self {
+        self.events = if enable {
+            self.events.union(event)
+        } else {
+            self.events.difference(event)
+        };
+        self
+    }
+
+    /// Enable/disable the X-axis high (or direction recognized) event.
+    pub const fn x_high(self, enable: bool) -> Self {
+        self.with_event(Int1CfgA::XHIE, enable)
+    }
+
+    /// Enable/disable the X-axis low (or direction recognized) event.
+    pub const fn x_low(self, enable: bool) -> Self {
+        self.with_event(Int1CfgA::XLIE, enable)
+    }
+
+    /// Enable/disable the Y-axis high (or direction recognized) event.
+    pub const fn y_high(self, enable: bool) -> Self {
+        self.with_event(Int1CfgA::YHIE, enable)
+    }
+
+    /// Enable/disable the Y-axis low (or direction recognized) event.
+    pub const fn y_low(self, enable: bool) -> Self {
+        self.with_event(Int1CfgA::YLIE, enable)
+    }
+
+    /// Enable/disable the Z-axis high (or direction recognized) event.
+    pub const fn z_high(self, enable: bool) -> Self {
+        self.with_event(Int1CfgA::ZHIE, enable)
+    }
+
+    /// Enable/disable the Z-axis low (or direction recognized) event.
+    pub const fn z_low(self, enable: bool) -> Self {
+        self.with_event(Int1CfgA::ZLIE, enable)
+    }
+
+    /// Set the threshold in m*g* (milli-*g*).
+    ///
+    /// The threshold is converted to register units according to the accelerometer
+    /// scale when the configuration is applied, so the scale should be set first.
+    /// The resulting value is clamped to the maximum of 127 LSB.
+    pub const fn threshold_mg(mut self, threshold: u16) -> Self {
+        self.threshold_mg = threshold;
+        self
+    }
+
+    /// Set the minimum event duration in samples (1/ODR).
+    ///
+    /// The duration is clamped to \[0, 127\].
+    pub const fn duration(mut self, samples: u8) -> Self {
+        self.duration = samples;
+        self
+    }
+
+    /// Latch the interrupt until the interrupt source is read.
+    pub const fn latched(mut self, latched: bool) -> Self {
+        self.latched = latched;
+        self
+    }
+}
+
+/// Inertial interrupt source
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct InterruptSource {
+    flags: Int1SrcA,
+}
+
+impl InterruptSource {
+    pub(crate) const fn new(flags: Int1SrcA) -> Self {
+        Self { flags }
+    }
+
+    /// One or more interrupt events have been generated.
+    #[inline]
+    pub const fn active(&self) -> bool {
+        self.flags.contains(Int1SrcA::IA)
+    }
+
+    /// X-axis high event has occurred.
+    #[inline]
+    pub const fn x_high(&self) -> bool {
+        self.flags.contains(Int1SrcA::XH)
+    }
+
+    /// X-axis low event has occurred.
+    #[inline]
+    pub const fn x_low(&self) -> bool {
+        self.flags.contains(Int1SrcA::XL)
+    }
+
+    /// Y-axis high event has occurred.
+    #[inline]
+    pub const fn y_high(&self) -> bool {
+        self.flags.contains(Int1SrcA::YH)
+    }
+
+    /// Y-axis low event has occurred.
+    #[inline]
+    pub const fn y_low(&self) -> bool {
+        self.flags.contains(Int1SrcA::YL)
+    }
+
+    /// Z-axis high event has occurred.
+    #[inline]
+    pub const fn z_high(&self) -> bool {
+        self.flags.contains(Int1SrcA::ZH)
+    }
+
+    /// Z-axis low event has occurred.
+    #[inline]
+    pub const fn z_low(&self) -> bool {
+        self.flags.contains(Int1SrcA::ZL)
+    }
+}
+
+/// Click (tap) detection configuration.
+///
+/// The default configuration has no clicks enabled, which disables click detection.
+///
+/// ```
+/// use lsm303agr::ClickConfig;
+///
+/// // Double click on the Z-axis.
+/// let config = ClickConfig::new()
+///     .z_double(true)
+///     .threshold_mg(1000)
+///     .time_limit_ms(50)
+///     .time_latency_ms(100)
+///     .time_window_ms(300);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClickConfig {
+    pub(crate) clicks: ClickCfgA,
+    pub(crate) threshold_mg: u16,
+    pub(crate) time_limit_ms: u16,
+    pub(crate) time_latency_ms: u16,
+    pub(crate) time_window_ms: u16,
+    pub(crate) latched: bool,
+}
+
+impl ClickConfig {
+    /// Create a new configuration with no clicks enabled.
+    pub const fn new() -> Self {
+        Self {
+            clicks: ClickCfgA::empty(),
+            threshold_mg: 0,
+            time_limit_ms: 0,
+            time_latency_ms: 0,
+            time_window_ms: 0,
+            latched: false,
+        }
+    }
+
+    const fn with_click(mut self, click: ClickCfgA, enable: bool) -> Self {
+        self.clicks = if enable {
+            self.clicks.union(click)
+        } else {
+            self.clicks.difference(click)
+        };
+        self
+    }
+
+    /// Enable/disable single click detection on the X-axis.
+    pub const fn x_single(self, enable: bool) -> Self {
+        self.with_click(ClickCfgA::XS, enable)
+    }
+
+    /// Enable/disable double click detection on the X-axis.
+    pub const fn x_double(self, enable: bool) -> Self {
+        self.with_click(ClickCfgA::XD, enable)
+    }
+
+    /// Enable/disable single click detection on the Y-axis.
+    pub const fn y_single(self, enable: bool) -> Self {
+        self.with_click(ClickCfgA::YS, enable)
+    }
+
+    /// Enable/disable double click detection on the Y-axis.
+    pub const fn y_double(self, enable: bool) -> Self {
+        self.with_click(ClickCfgA::YD, enable)
+    }
+
+    /// Enable/disable single click detection on the Z-axis.
+    pub const fn z_single(self, enable: bool) -> Self {
+        self.with_click(ClickCfgA::ZS, enable)
+    }
+
+    /// Enable/disable double click detection on the Z-axis.
+    pub const fn z_double(self, enable: bool) -> Self {
+        self.with_click(ClickCfgA::ZD, enable)
+    }
+
+    /// Set the click threshold in m*g* (milli-*g*).
+    ///
+    /// The threshold is converted to register units according to the accelerometer
+    /// scale when the configuration is applied, so the scale should be set first.
+    /// The resulting value is clamped to the maximum of 127 LSB.
+    pub const fn threshold_mg(mut self, threshold: u16) -> Self {
+        self.threshold_mg = threshold;
+        self
+    }
+
+    /// Set the maximum time in milliseconds between the start of a click and
+    /// the acceleration falling back below the threshold.
+    ///
+    /// The time is clamped to 127 samples (1/ODR).
+    pub const fn time_limit_ms(mut self, ms: u16) -> Self {
+        self.time_limit_ms = ms;
+        self
+    }
+
+    /// Set the time in milliseconds after the first click during which the
+    /// click detection is disabled (double click only).
+    ///
+    /// The time is clamped to 255 samples (1/ODR).
+    pub const fn time_latency_ms(mut self, ms: u16) -> Self {
+        self.time_latency_ms = ms;
+        self
+    }
+
+    /// Set the time window in milliseconds after the latency time during which
+    /// the second click can start (double click only).
+    ///
+    /// The time is clamped to 255 samples (1/ODR).
+    pub const fn time_window_ms(mut self, ms: u16) -> Self {
+        self.time_window_ms = ms;
+        self
+    }
+
+    /// Latch the interrupt until the click source is read.
+    pub const fn latched(mut self, latched: bool) -> Self {
+        self.latched = latched;
+        self
+    }
+}
+
+/// Click (tap) source
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClickSource {
+    flags: ClickSrcA,
+}
+
+impl ClickSource {
+    pub(crate) const fn new(flags: ClickSrcA) -> Self {
+        Self { flags }
+    }
+
+    /// One or more click events have been generated.
+    #[inline]
+    pub const fn active(&self) -> bool {
+        self.flags.contains(ClickSrcA::IA)
+    }
+
+    /// Single click detected.
+    #[inline]
+    pub const fn single_click(&self) -> bool {
+        self.flags.contains(ClickSrcA::SCLICK)
+    }
+
+    /// Double click detected.
+    #[inline]
+    pub const fn double_click(&self) -> bool {
+        self.flags.contains(ClickSrcA::DCLICK)
+    }
+
+    /// Click detected in negative direction.
+    #[inline]
+    pub const fn negative(&self) -> bool {
+        self.flags.contains(ClickSrcA::SIGN)
+    }
+
+    /// Click detected on X-axis.
+    #[inline]
+    pub const fn x(&self) -> bool {
+        self.flags.contains(ClickSrcA::X)
+    }
+
+    /// Click detected on Y-axis.
+    #[inline]
+    pub const fn y(&self) -> bool {
+        self.flags.contains(ClickSrcA::Y)
+    }
+
+    /// Click detected on Z-axis.
+    #[inline]
+    pub const fn z(&self) -> bool {
+        self.flags.contains(ClickSrcA::Z)
+    }
+}
+
+/// Magnetometer threshold interrupt configuration.
+///
+/// The default configuration has no axes enabled, which disables the interrupt.
+///
+/// ```
+/// use lsm303agr::{InterruptPolarity, MagInterruptConfig};
+///
+/// // Detect a magnet moving close to the sensor on the Z-axis.
+/// let config = MagInterruptConfig::new()
+///     .z(true)
+///     .threshold_nt(100_000)
+///     .polarity(InterruptPolarity::ActiveHigh)
+///     .latched(true)
+///     .int_mag_pin(true);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MagInterruptConfig {
+    pub(crate) ctrl: IntCtrlRegM,
+    pub(crate) threshold_nt: u32,
+    pub(crate) int_mag_pin: bool,
+}
+
+impl Default for MagInterruptConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MagInterruptConfig {
+    /// Create a new configuration with no axes enabled.
+    pub const fn new() -> Self {
+        Self {
+            ctrl: IntCtrlRegM::IEA,
+            threshold_nt: 0,
+            int_mag_pin: false,
+        }
+    }
+
+    const fn with_flag(mut self, flag: IntCtrlRegM, enable: bool) -> Self {
+        self.ctrl = if enable {
+            self.ctrl.union(flag)
+        } else {
+            self.ctrl.difference(flag)
+        };
+        self
+    }
+
+    /// Enable/disable the interrupt recognition on the X-axis.
+    pub const fn x(self, enable: bool) -> Self {
+        self.with_flag(IntCtrlRegM::XIEN, enable)
+    }
+
+    /// Enable/disable the interrupt recognition on the Y-axis.
+    pub const fn y(self, enable: bool) -> Self {
+        self.with_flag(IntCtrlRegM::YIEN, enable)
+    }
+
+    /// Enable/disable the interrupt recognition on the Z-axis.
+    pub const fn z(self, enable: bool) -> Self {
+        self.with_flag(IntCtrlRegM::ZIEN, enable)
+    }
+
+    /// Set the interrupt polarity.
+    pub const fn polarity(mut self, polarity: InterruptPolarity) -> Self {
+        self.ctrl = self.ctrl.with_polarity(polarity);
+        self
+    }
+
+    /// Latch the interrupt until the interrupt source is read.
+    pub const fn latched(self, latched: bool) -> Self {
+        self.with_flag(IntCtrlRegM::IEL, latched)
+    }
+
+    /// Set the threshold in nT (nano-Tesla).
+    ///
+    /// The threshold applies to the absolute value of the magnetic field on each
+    /// enabled axis. It is rounded to the nearest LSB (150 nT) and clamped to the
+    /// maximum of 32767 LSB.
+    pub const fn threshold_nt(mut self, threshold: u32) -> Self {
+        self.threshold_nt = threshold;
+        self
+    }
+
+    /// Route the interrupt to the INT_MAG pin.
+    pub const fn int_mag_pin(mut self, enable: bool) -> Self {
+        self.int_mag_pin = enable;
+        self
+    }
+}
+
+/// Magnetometer threshold interrupt source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MagInterruptSource {
+    flags: IntSourceRegM,
+    polarity: InterruptPolarity,
+}
+
+impl MagInterruptSource {
+    pub(crate) const fn new(flags: IntSourceRegM, polarity: InterruptPolarity) -> Self {
+        Self { flags, polarity }
+    }
+
+    /// An interrupt event has occurred.
+    #[inline]
+    pub const fn active(&self) -> bool {
+        let int = self.flags.contains(IntSourceRegM::INT);
+        match self.polarity {
+            InterruptPolarity::ActiveHigh => int,
+            InterruptPolarity::ActiveLow => !int,
+        }
+    }
+
+    /// X-axis value exceeds the threshold on the positive side.
+    #[inline]
+    pub const fn x_positive(&self) -> bool {
+        self.flags.contains(IntSourceRegM::P_TH_S_X)
+    }
+
+    /// Y-axis value exceeds the threshold on the positive side.
+    #[inline]
+    pub const fn y_positive(&self) -> bool {
+        self.flags.contains(IntSourceRegM::P_TH_S_Y)
+    }
+
+    /// Z-axis value exceeds the threshold on the positive side.
+    #[inline]
+    pub const fn z_positive(&self) -> bool {
+        self.flags.contains(IntSourceRegM::P_TH_S_Z)
+    }
+
+    /// X-axis value exceeds the threshold on the negative side.
+    #[inline]
+    pub const fn x_negative(&self) -> bool {
+        self.flags.contains(IntSourceRegM::N_TH_S_X)
+    }
+
+    /// Y-axis value exceeds the threshold on the negative side.
+    #[inline]
+    pub const fn y_negative(&self) -> bool {
+        self.flags.contains(IntSourceRegM::N_TH_S_Y)
+    }
+
+    /// Z-axis value exceeds the threshold on the negative side.
+    #[inline]
+    pub const fn z_negative(&self) -> bool {
+        self.flags.contains(IntSourceRegM::N_TH_S_Z)
+    }
+
+    /// Internal measurement range overflow.
+    #[inline]
+    pub const fn overflow(&self) -> bool {
+        self.flags.contains(IntSourceRegM::MROI)
+    }
+}
+
+/// Self-test result.
+///
+/// The differences between the measurements with and without self-test
+/// are given in m*g* (milli-*g*) for the accelerometer and in mG (milli-Gauss)
+/// for the magnetometer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelfTestReport {
+    delta: (i32, i32, i32),
+    limits: (i32, i32),
+}
+
+impl SelfTestReport {
+    pub(crate) const fn new(delta: (i32, i32, i32), limits: (i32, i32)) -> Self {
+        Self { delta, limits }
+    }
+
+    const fn within_limits(&self, delta: i32) -> bool {
+        delta >= self.limits.0 && delta <= self.limits.1
+    }
+
+    /// Absolute difference in X-direction.
+    #[inline]
+    pub const fn x_delta(&self) -> i32 {
+        self.delta.0
+    }
+
+    /// Absolute difference in Y-direction.
+    #[inline]
+    pub const fn y_delta(&self) -> i32 {
+        self.delta.1
+    }
+
+    /// Absolute difference in Z-direction.
+    #[inline]
+    pub const fn z_delta(&self) -> i32 {
+        self.delta.2
+    }
+
+    /// Absolute difference in X-, Y- and Z-directions.
+    #[inline]
+    pub const fn xyz_delta(&self) -> (i32, i32, i32) {
+        self.delta
+    }
+
+    /// Minimum and maximum accepted difference.
+    #[inline]
+    pub const fn limits(&self) -> (i32, i32) {
+        self.limits
+    }
+
+    /// X-axis passed the self-test.
+    #[inline]
+    pub const fn x_passed(&self) -> bool {
+        self.within_limits(self.delta.0)
+    }
+
+    /// Y-axis passed the self-test.
+    #[inline]
+    pub const fn y_passed(&self) -> bool {
+        self.within_limits(self.delta.1)
+    }
+
+    /// Z-axis passed the self-test.
+    #[inline]
+    pub const fn z_passed(&self) -> bool {
+        self.within_limits(self.delta.2)
+    }
+
+    /// All axes passed the self-test.
+    #[inline]
+    pub const fn passed(&self) -> bool {
+        self.x_passed() && self.y_passed() && self.z_passed()
+    }
+}
+
+/// Device configuration snapshot.
+///
+/// This contains the accelerometer and magnetometer control registers cached in
+/// the driver: `CTRL_REG1_A` to `CTRL_REG6_A`, `TEMP_CFG_REG_A`, `FIFO_CTRL_REG_A`,
+/// `CFG_REG_A_M` to `CFG_REG_C_M` and `INT_CTRL_REG_M`. These hold the modes,
+/// output data rates, scale, filters, FIFO mode, interrupt pin routing and
+/// interrupt enable flags.
+///
+/// The interrupt generator, click and activity detection configuration together
+/// with their thresholds and durations, the magnetometer interrupt threshold and
+/// the hard-iron offsets are not included and must be configured again separately.
+///
+/// It can be stored as bytes, for example in flash memory, and applied
+/// again later. See [`config()`](crate::Lsm303agr::config) and
+/// [`apply_config()`](crate::Lsm303agr::apply_config).
+///
+/// ```
+/// use lsm303agr::DeviceConfig;
+///
+/// let bytes = DeviceConfig::default().to_bytes();
+/// let config = DeviceConfig::from_bytes(&bytes).unwrap();
+/// assert_eq!(config, DeviceConfig::default());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DeviceConfig {
+    pub(crate) ctrl_reg1_a: CtrlReg1A,
+    pub(crate) ctrl_reg2_a: CtrlReg2A,
+    pub(crate) ctrl_reg3_a: CtrlReg3A,
+    pub(crate) ctrl_reg4_a: CtrlReg4A,
+    pub(crate) ctrl_reg5_a: CtrlReg5A,
+    pub(crate) ctrl_reg6_a: CtrlReg6A,
+    pub(crate) temp_cfg_reg_a: TempCfgRegA,
+    pub(crate) fifo_ctrl_reg_a: FifoCtrlRegA,
+    pub(crate) cfg_reg_a_m: CfgRegAM,
+    pub(crate) cfg_reg_b_m: CfgRegBM,
+    pub(crate) cfg_reg_c_m: CfgRegCM,
+    pub(crate) int_ctrl_reg_m: IntCtrlRegM,
+}
+
+impl DeviceConfig {
+    /// Serialized configuration size in bytes.
+    pub const SIZE: usize = 13;
+
+    /// Serialized configuration format version.
+    const VERSION: u8 = 1;
+
+    /// Serialize the configuration.
+    pub const fn to_bytes(&self) -> [u8; Self::SIZE] {
+        [
+            Self::VERSION,
+            self.ctrl_reg1_a.bits(),
+            self.ctrl_reg2_a.bits(),
+            self.ctrl_reg3_a.bits(),
+            self.ctrl_reg4_a.bits(),
+            self.ctrl_reg5_a.bits(),
+            self.ctrl_reg6_a.bits(),
+            self.temp_cfg_reg_a.bits(),
+            self.fifo_ctrl_reg_a.bits(),
+            self.cfg_reg_a_m.bits(),
+            self.cfg_reg_b_m.bits(),
+            self.cfg_reg_c_m.bits(),
+            self.int_ctrl_reg_m.bits(),
+        ]
+    }
+
+    /// Deserialize a configuration.
+    ///
+    /// Returns `None` if the bytes were not created by [`to_bytes()`](DeviceConfig::to_bytes)
+    /// with a compatible format version.
+    pub const fn from_bytes(bytes: &[u8; Self::SIZE]) -> Option<Self> {
+        if bytes[0] != Self::VERSION {
+            return None;
+        }
+
+        Some(Self {
+            ctrl_reg1_a: CtrlReg1A::from_bits_truncate(bytes[1]),
+            ctrl_reg2_a: CtrlReg2A::from_bits_truncate(bytes[2]),
+            ctrl_reg3_a: CtrlReg3A::from_bits_truncate(bytes[3]),
+            ctrl_reg4_a: CtrlReg4A::from_bits_truncate(bytes[4]),
+            ctrl_reg5_a: CtrlReg5A::from_bits_truncate(bytes[5]),
+            ctrl_reg6_a: CtrlReg6A::from_bits_truncate(bytes[6]),
+            temp_cfg_reg_a: TempCfgRegA::from_bits_truncate(bytes[7]),
+            fifo_ctrl_reg_a: FifoCtrlRegA::from_bits_truncate(bytes[8]),
+            cfg_reg_a_m: CfgRegAM::from_bits_truncate(bytes[9]),
+            cfg_reg_b_m: CfgRegBM::from_bits_truncate(bytes[10]),
+            cfg_reg_c_m: CfgRegCM::from_bits_truncate(bytes[11]),
+            int_ctrl_reg_m: IntCtrlRegM::from_bits_truncate(bytes[12]),
+        })
+    }
+
+    /// Accelerometer mode.
+    pub const fn accel_mode(&self) -> AccelMode {
+        AccelMode::from_registers(self.ctrl_reg1_a, self.ctrl_reg4_a)
+    }
+
+    /// Accelerometer output data rate.
+    ///
+    /// Returns `None` if the accelerometer is powered down.
+    pub const fn accel_odr(&self) -> Option<AccelOutputDataRate> {
+        self.ctrl_reg1_a.odr()
+    }
+
+    /// Accelerometer scale.
+    pub const fn accel_scale(&self) -> AccelScale {
+        self.ctrl_reg4_a.scale()
+    }
+
+    /// Magnetometer mode.
+    pub const fn mag_mode(&self) -> MagMode {
+        self.cfg_reg_a_m.mode()
+    }
+
+    /// Magnetometer output data rate.
+    pub const fn mag_odr(&self) -> MagOutputDataRate {
+        self.cfg_reg_a_m.odr()
+    }
+
+    /// Magnetometer low-pass filter enabled.
+    pub const fn mag_low_pass_filter(&self) -> bool {
+        self.cfg_reg_b_m.contains(CfgRegBM::LPF)
+    }
+
+    /// Magnetometer offset cancellation enabled.
+    pub const fn mag_offset_cancellation(&self) -> bool {
+        self.cfg_reg_b_m.offset_cancellation()
+    }
+
+    /// Magnetometer set pulse frequency.
+    pub const fn mag_set_pulse_frequency(&self) -> MagSetPulseFrequency {
+        self.cfg_reg_b_m.set_pulse_frequency()
+    }
+}
+
+/// Register dump.
+///
+/// This contains the raw values of the accelerometer registers
+/// `0x07` to `0x3F` and the magnetometer registers `0x45` to `0x6F`.
+/// The [`Display`](core::fmt::Display) implementation lists the documented
+/// registers together with the names of the bits set.
+/// See [`dump_registers()`](crate::Lsm303agr::dump_registers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterDump {
+    pub(crate) accel: [u8; RegisterDump::ACCEL_LEN],
+    pub(crate) mag: [u8; RegisterDump::MAG_LEN],
+}
+
+impl RegisterDump {
+    /// First accelerometer register address.
+    pub const ACCEL_FIRST: u8 = 0x07;
+    /// Last accelerometer register address.
+    pub const ACCEL_LAST: u8 = 0x3F;
+    /// First magnetometer register address.
+    pub const MAG_FIRST: u8 = 0x45;
+    /// Last magnetometer register address.
+    pub const MAG_LAST: u8 = 0x6F;
+
+    pub(crate) const ACCEL_LEN: usize = (Self::ACCEL_LAST - Self::ACCEL_FIRST) as usize + 1;
+    pub(crate) const MAG_LEN: usize = (Self::MAG_LAST - Self::MAG_FIRST) as usize + 1;
+
+    /// Value of the accelerometer register at `address`.
+    ///
+    /// Returns `None` if the address is outside of the dumped range.
+    pub const fn accel_register(&self, address: u8) -> Option<u8> {
+        if address < Self::ACCEL_FIRST || address > Self::ACCEL_LAST {
+            return None;
+        }
+        Some(self.accel[(address - Self::ACCEL_FIRST) as usize])
+    }
+
+    /// Value of the magnetometer register at `address`.
+    ///
+    /// Returns `None` if the address is outside of the dumped range.
+    pub const fn mag_register(&self, address: u8) -> Option<u8> {
+        if address < Self::MAG_FIRST || address > Self::MAG_LAST {
+            return None;
+        }
+        Some(self.mag[(address - Self::MAG_FIRST) as usize])
+    }
+
+    /// Raw values of the accelerometer registers, starting at `ACCEL_FIRST`.
+    pub const fn accel_registers(&self) -> &[u8] {
+        &self.accel
+    }
+
+    /// Raw values of the magnetometer registers, starting at `MAG_FIRST`.
+    pub const fn mag_registers(&self) -> &[u8] {
+        &self.mag
+    }
+}
@@ -0,0 +1,137 @@
+mod common;
+use crate::common::{
+    destroy_i2c, destroy_spi, new_i2c, new_spi_accel, BitFlags as BF, Register, ACCEL_ADDR,
+    DEFAULT_CTRL_REG1_A, HZ50,
+};
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay as Delay, i2c::Transaction as I2cTrans, spi::Transaction as SpiTrans,
+};
+use lsm303agr::{AccelMode, AccelOutputDataRate, AccelScale, Acceleration};
+
+macro_rules! fifo_status {
+    ($name:ident, $reg:expr, $watermark:expr, $overrun:expr, $empty:expr, $len:expr) => {
+        #[test]
+        fn $name() {
+            let mut sensor = new_i2c(&[I2cTrans::write_read(
+                ACCEL_ADDR,
+                vec![Register::FIFO_SRC_REG_A],
+                vec![$reg],
+            )]);
+            let status = sensor.fifo_status().unwrap();
+            assert_eq!(status.watermark(), $watermark);
+            assert_eq!(status.overrun(), $overrun);
+            assert_eq!(status.is_empty(), $empty);
+            assert_eq!(status.len(), $len);
+            destroy_i2c(sensor);
+        }
+    };
+}
+
+fifo_status!(fifo_empty, BF::EMPTY, false, false, true, 0);
+fifo_status!(fifo_partial, 5, false, false, false, 5);
+fifo_status!(fifo_watermark, BF::WTM | 20, true, false, false, 20);
+fifo_status!(
+    fifo_full,
+    BF::WTM | BF::OVRN_FIFO | 31,
+    true,
+    true,
+    false,
+    32
+);
+
+fn empty_samples() -> [Acceleration; 4] {
+    let mut sensor = new_i2c(&[I2cTrans::write_read(
+        ACCEL_ADDR,
+        vec![Register::OUT_X_L_A | 0x80],
+        vec![0; 6],
+    )]);
+    let sample = sensor.acceleration().unwrap();
+    destroy_i2c(sensor);
+    [sample; 4]
+}
+
+#[test]
+fn can_read_fifo_i2c() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, DEFAULT_CTRL_REG1_A | HZ50],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0b01 << 4]),
+        I2cTrans::write_read(ACCEL_ADDR, vec![Register::FIFO_SRC_REG_A], vec![2]),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            vec![
+                0x10, 0x20, 0x30, 0x40, 0x50, 0x60, 0x11, 0x21, 0x31, 0x41, 0x51, 0x61,
+            ],
+        ),
+    ]);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz50)
+        .unwrap();
+    sensor.set_accel_scale(AccelScale::G4).unwrap();
+
+    let mut samples = empty_samples();
+    assert_eq!(sensor.read_fifo(&mut samples).unwrap(), 2);
+
+    assert_eq!(samples[0].xyz_raw(), (0x2010, 0x4030, 0x6050));
+    assert_eq!(samples[1].xyz_raw(), (0x2111, 0x4131, 0x6151));
+    assert_eq!(
+        samples[0].xyz_unscaled(),
+        (0x2010 >> 6, 0x4030 >> 6, 0x6050 >> 6)
+    );
+    assert_eq!(samples[1].x_mg(), (0x2111 >> 6) * 8);
+    assert_eq!(samples[2].xyz_raw(), (0, 0, 0));
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn read_fifo_is_limited_by_buffer_length() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write_read(ACCEL_ADDR, vec![Register::FIFO_SRC_REG_A], vec![10]),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            (0..24).collect(),
+        ),
+    ]);
+    let mut samples = empty_samples();
+    assert_eq!(sensor.read_fifo(&mut samples).unwrap(), 4);
+    assert_eq!(samples[3].x_raw(), u16::from_le_bytes([18, 19]));
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn read_empty_fifo_reads_no_data() {
+    let mut sensor = new_i2c(&[I2cTrans::write_read(
+        ACCEL_ADDR,
+        vec![Register::FIFO_SRC_REG_A],
+        vec![BF::EMPTY],
+    )]);
+    let mut samples = empty_samples();
+    assert_eq!(sensor.read_fifo(&mut samples).unwrap(), 0);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_read_full_fifo_spi() {
+    let data: Vec<u8> = (0..192).map(|i| i as u8).collect();
+    let mut sensor = new_spi_accel(&[
+        SpiTrans::transaction_start(),
+        SpiTrans::transfer_in_place(
+            vec![BF::SPI_RW | Register::FIFO_SRC_REG_A, 0],
+            vec![0, BF::OVRN_FIFO | 31],
+        ),
+        SpiTrans::transaction_end(),
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![BF::SPI_RW | BF::SPI_MS | Register::OUT_X_L_A]),
+        SpiTrans::read_vec(data),
+        SpiTrans::transaction_end(),
+    ]);
+    let mut samples = [empty_samples()[0]; 40];
+    assert_eq!(sensor.read_fifo(&mut samples).unwrap(), 32);
+    assert_eq!(samples[31].z_raw(), u16::from_le_bytes([190, 191]));
+    destroy_spi(sensor);
+}
@@ -0,0 +1,97 @@
+mod common;
+use crate::common::{
+    destroy_i2c, destroy_spi, new_i2c, new_spi_accel, BitFlags as BF, Register, ACCEL_ADDR,
+};
+use embedded_hal_mock::eh1::{i2c::Transaction as I2cTrans, spi::Transaction as SpiTrans};
+use lsm303agr::{AccelHighPassConfig, AccelHighPassCutoff, AccelHighPassMode};
+
+macro_rules! set_hp {
+    ($name:ident, $mode:ident, $cutoff:ident, $value:expr) => {
+        #[test]
+        fn $name() {
+            let mut sensor = new_i2c(&[I2cTrans::write(
+                ACCEL_ADDR,
+                vec![Register::CTRL_REG2_A, $value],
+            )]);
+            sensor
+                .acc_set_high_pass_filter(AccelHighPassConfig {
+                    mode: AccelHighPassMode::$mode,
+                    cutoff: AccelHighPassCutoff::$cutoff,
+                    ..Default::default()
+                })
+                .unwrap();
+            destroy_i2c(sensor);
+        }
+    };
+}
+
+set_hp!(hp_normal_with_reset, NormalWithReset, Highest, 0);
+set_hp!(hp_reference, Reference, Highest, 0b0100_0000);
+set_hp!(hp_normal, Normal, Highest, 0b1000_0000);
+set_hp!(hp_autoreset, AutoResetOnInterrupt, Highest, 0b1100_0000);
+set_hp!(hp_cutoff_high, NormalWithReset, High, 0b0001_0000);
+set_hp!(hp_cutoff_low, NormalWithReset, Low, 0b0010_0000);
+set_hp!(hp_cutoff_lowest, NormalWithReset, Lowest, 0b0011_0000);
+
+#[test]
+fn can_enable_high_pass_filter_paths() {
+    let mut sensor = new_i2c(&[
+        // Output data and FIFO
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0b1000_1000]),
+        // Click and both interrupt generators
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0b0000_0111]),
+        // Bypass
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]),
+    ]);
+    sensor
+        .acc_set_high_pass_filter(AccelHighPassConfig {
+            mode: AccelHighPassMode::Normal,
+            output_data: true,
+            ..Default::default()
+        })
+        .unwrap();
+    sensor
+        .acc_set_high_pass_filter(AccelHighPassConfig {
+            click: true,
+            aoi1: true,
+            aoi2: true,
+            ..Default::default()
+        })
+        .unwrap();
+    sensor
+        .acc_set_high_pass_filter(AccelHighPassConfig::default())
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_reset_high_pass_filter_i2c() {
+    let mut sensor = new_i2c(&[I2cTrans::write_read(
+        ACCEL_ADDR,
+        vec![Register::REFERENCE_A],
+        vec![0x12],
+    )]);
+    sensor.acc_reset_high_pass_filter().unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_reset_high_pass_filter_spi() {
+    let mut sensor = new_spi_accel(&[
+        SpiTrans::transaction_start(),
+        SpiTrans::transfer_in_place(vec![BF::SPI_RW | Register::REFERENCE_A, 0], vec![0, 0x12]),
+        SpiTrans::transaction_end(),
+    ]);
+    sensor.acc_reset_high_pass_filter().unwrap();
+    destroy_spi(sensor);
+}
+
+#[test]
+fn can_set_high_pass_reference() {
+    let mut sensor = new_i2c(&[I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::REFERENCE_A, 0xAB],
+    )]);
+    sensor.acc_set_high_pass_reference(0xAB).unwrap();
+    destroy_i2c(sensor);
+}
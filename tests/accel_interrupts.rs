@@ -0,0 +1,393 @@
+mod common;
+use crate::common::{
+    destroy_i2c, new_i2c, BitFlags as BF, Register, ACCEL_ADDR, DEFAULT_CTRL_REG1_A, HZ50,
+};
+use embedded_hal_mock::eh1::{delay::NoopDelay as Delay, i2c::Transaction as I2cTrans};
+use lsm303agr::{
+    AccelMode, AccelOutputDataRate, AccelScale, ClickConfig, Error, InertialInterruptConfig,
+    InertialInterruptMode, Interrupt, InterruptGenerator, InterruptPin, InterruptPolarity,
+};
+
+#[test]
+fn can_configure_inertial_interrupt_1() {
+    let mut sensor = new_i2c(&[
+        // 250 mg / 16 mg ≈ 16
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_THS_A, 16]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_DURATION_A, 2]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::INT1_CFG_A, BF::XHIE | BF::YHIE | BF::ZHIE],
+        ),
+    ]);
+    sensor
+        .acc_set_inertial_interrupt(
+            InterruptGenerator::Aoi1,
+            InertialInterruptConfig::new()
+                .x_high(true)
+                .y_high(true)
+                .z_high(true)
+                .threshold_mg(250)
+                .duration(2),
+        )
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_configure_inertial_interrupt_2() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT2_THS_A, 22]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT2_DURATION_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, BF::LIR_INT2]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![
+                Register::INT2_CFG_A,
+                BF::AOI | BF::XLIE | BF::YLIE | BF::ZLIE,
+            ],
+        ),
+    ]);
+    // Free-fall detection
+    sensor
+        .acc_set_inertial_interrupt(
+            InterruptGenerator::Aoi2,
+            InertialInterruptConfig::new()
+                .mode(InertialInterruptMode::And)
+                .x_low(true)
+                .y_low(true)
+                .z_low(true)
+                .threshold_mg(350)
+                .latched(true),
+        )
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn inertial_interrupt_4d_position() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_THS_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_DURATION_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, BF::D4D_INT1]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::INT1_CFG_A, BF::AOI | BF::D6 | BF::XHIE | BF::XLIE],
+        ),
+    ]);
+    sensor
+        .acc_set_inertial_interrupt(
+            InterruptGenerator::Aoi1,
+            InertialInterruptConfig::new()
+                .mode(InertialInterruptMode::Position4D)
+                .x_high(true)
+                .x_low(true),
+        )
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn inertial_interrupt_threshold_depends_on_scale() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0b0011_0000]),
+        // 1000 mg / 186 mg ≈ 5
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_THS_A, 5]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_DURATION_A, 0x7F]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_CFG_A, BF::ZHIE]),
+    ]);
+    sensor.set_accel_scale(AccelScale::G16).unwrap();
+    sensor
+        .acc_set_inertial_interrupt(
+            InterruptGenerator::Aoi1,
+            InertialInterruptConfig::new()
+                .z_high(true)
+                .threshold_mg(1000)
+                .duration(200),
+        )
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn inertial_interrupt_threshold_is_clamped() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_THS_A, 0x7F]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_DURATION_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::INT1_CFG_A, BF::ZHIE]),
+    ]);
+    sensor
+        .acc_set_inertial_interrupt(
+            InterruptGenerator::Aoi1,
+            InertialInterruptConfig::new()
+                .z_high(true)
+                .threshold_mg(4000),
+        )
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_read_interrupt_source() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write_read(ACCEL_ADDR, vec![Register::INT1_SRC_A], vec![0b0100_0010]),
+        I2cTrans::write_read(ACCEL_ADDR, vec![Register::INT2_SRC_A], vec![0b0001_0101]),
+    ]);
+    let src = sensor
+        .read_interrupt_source(InterruptGenerator::Aoi1)
+        .unwrap();
+    assert!(src.active());
+    assert!(src.x_high());
+    assert!(!src.x_low());
+    assert!(!src.y_high());
+    assert!(!src.y_low());
+    assert!(!src.z_high());
+    assert!(!src.z_low());
+
+    let src = sensor
+        .read_interrupt_source(InterruptGenerator::Aoi2)
+        .unwrap();
+    assert!(!src.active());
+    assert!(!src.x_high());
+    assert!(src.x_low());
+    assert!(!src.y_high());
+    assert!(src.y_low());
+    assert!(!src.z_high());
+    assert!(src.z_low());
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_enable_disable_interrupts_on_int1() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG3_A, 0b0100_0000]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG3_A, 0]),
+    ]);
+    sensor
+        .acc_enable_interrupt_on(InterruptPin::Int1, Interrupt::Aoi1)
+        .unwrap();
+    sensor
+        .acc_disable_interrupt_on(InterruptPin::Int1, Interrupt::Aoi1)
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_enable_disable_interrupts_on_int2() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, BF::I2_INT1]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG6_A, BF::I2_INT1 | BF::I2_INT2],
+        ),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![
+                Register::CTRL_REG6_A,
+                BF::I2_CLICK_EN | BF::I2_INT1 | BF::I2_INT2,
+            ],
+        ),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG6_A, BF::I2_CLICK_EN | BF::I2_INT2],
+        ),
+    ]);
+    sensor
+        .acc_enable_interrupt_on(InterruptPin::Int2, Interrupt::Aoi1)
+        .unwrap();
+    sensor
+        .acc_enable_interrupt_on(InterruptPin::Int2, Interrupt::Aoi2)
+        .unwrap();
+    sensor
+        .acc_enable_interrupt_on(InterruptPin::Int2, Interrupt::Click)
+        .unwrap();
+    sensor
+        .acc_disable_interrupt_on(InterruptPin::Int2, Interrupt::Aoi1)
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+macro_rules! not_on_int2 {
+    ($name:ident, $interrupt:ident) => {
+        #[test]
+        fn $name() {
+            let mut sensor = new_i2c(&[]);
+            assert!(matches!(
+                sensor.acc_enable_interrupt_on(InterruptPin::Int2, Interrupt::$interrupt),
+                Err(Error::InvalidInputData)
+            ));
+            assert!(matches!(
+                sensor.acc_disable_interrupt_on(InterruptPin::Int2, Interrupt::$interrupt),
+                Err(Error::InvalidInputData)
+            ));
+            destroy_i2c(sensor);
+        }
+    };
+}
+
+not_on_int2!(data_ready1_not_on_int2, DataReady1);
+not_on_int2!(data_ready2_not_on_int2, DataReady2);
+not_on_int2!(fifo_watermark_not_on_int2, FifoWatermark);
+not_on_int2!(fifo_overrun_not_on_int2, FifoOverrun);
+
+#[test]
+fn can_set_interrupt_polarity() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, BF::H_LACTIVE]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG6_A, BF::H_LACTIVE | BF::I2_INT2],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, BF::I2_INT2]),
+    ]);
+    assert_eq!(
+        sensor.acc_interrupt_polarity(),
+        InterruptPolarity::ActiveHigh
+    );
+    sensor
+        .acc_set_interrupt_polarity(InterruptPolarity::ActiveLow)
+        .unwrap();
+    assert_eq!(
+        sensor.acc_interrupt_polarity(),
+        InterruptPolarity::ActiveLow
+    );
+    sensor
+        .acc_enable_interrupt_on(InterruptPin::Int2, Interrupt::Aoi2)
+        .unwrap();
+    sensor
+        .acc_set_interrupt_polarity(InterruptPolarity::ActiveHigh)
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_configure_click_detection() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        // 1000 mg / 16 mg ≈ 63, latched
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_THS_A, 0x80 | 63]),
+        // 50 ms at 50 Hz
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_LIMIT_A, 3]),
+        // 100 ms at 50 Hz
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_LATENCY_A, 5]),
+        // 300 ms at 50 Hz
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_WINDOW_A, 15]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_CFG_A, 0b0010_0001]),
+    ]);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz50)
+        .unwrap();
+    sensor
+        .acc_set_click_detection(
+            ClickConfig::new()
+                .x_single(true)
+                .z_double(true)
+                .threshold_mg(1000)
+                .time_limit_ms(50)
+                .time_latency_ms(100)
+                .time_window_ms(300)
+                .latched(true),
+        )
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn click_timing_is_clamped() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, 7 << 4 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_THS_A, 0x7F]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_LIMIT_A, 0x7F]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_LATENCY_A, 0xFF]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_WINDOW_A, 0xFF]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_CFG_A, 0b0001_0101]),
+    ]);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz400)
+        .unwrap();
+    sensor
+        .acc_set_click_detection(
+            ClickConfig::new()
+                .x_single(true)
+                .y_single(true)
+                .z_single(true)
+                .threshold_mg(u16::MAX)
+                .time_limit_ms(1000)
+                .time_latency_ms(1000)
+                .time_window_ms(1000),
+        )
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn click_detection_requires_odr() {
+    let mut sensor = new_i2c(&[]);
+    assert!(matches!(
+        sensor.acc_set_click_detection(ClickConfig::new().z_single(true)),
+        Err(Error::InvalidInputData)
+    ));
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_read_click_source() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write_read(ACCEL_ADDR, vec![Register::CLICK_SRC_A], vec![0b0101_1100]),
+        I2cTrans::write_read(ACCEL_ADDR, vec![Register::CLICK_SRC_A], vec![0b0110_0001]),
+    ]);
+    let src = sensor.click_source().unwrap();
+    assert!(src.active());
+    assert!(src.single_click());
+    assert!(!src.double_click());
+    assert!(src.negative());
+    assert!(!src.x());
+    assert!(!src.y());
+    assert!(src.z());
+
+    let src = sensor.click_source().unwrap();
+    assert!(src.active());
+    assert!(!src.single_click());
+    assert!(src.double_click());
+    assert!(!src.negative());
+    assert!(src.x());
+    assert!(!src.y());
+    assert!(!src.z());
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_configure_activity_detection() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::ACT_DUR_A, 10]),
+        // 80 mg / 16 mg = 5
+        I2cTrans::write(ACCEL_ADDR, vec![Register::ACT_THS_A, 5]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, BF::P2_ACT]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::ACT_THS_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, 0]),
+    ]);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz50)
+        .unwrap();
+    sensor.set_activity_detection(80, 10).unwrap();
+    sensor.acc_route_activity_to_int2(true).unwrap();
+    assert_eq!(sensor.get_accel_mode(), AccelMode::Normal);
+    sensor.disable_activity_detection().unwrap();
+    sensor.acc_route_activity_to_int2(false).unwrap();
+    destroy_i2c(sensor);
+}
@@ -3,7 +3,7 @@ use crate::common::{
     destroy_i2c, new_i2c, BitFlags as BF, Register, ACCEL_ADDR, DEFAULT_CTRL_REG1_A,
 };
 use embedded_hal_mock::eh1::{delay::NoopDelay as Delay, i2c::Transaction as I2cTrans};
-use lsm303agr::{AccelMode as Mode, AccelOutputDataRate as ODR, FifoMode, Interrupt};
+use lsm303agr::{AccelMode as Mode, AccelOutputDataRate as ODR, AxisSet, FifoMode, Interrupt};
 
 macro_rules! low_pwr {
     ($name:ident, $hz:ident, $value:expr) => {
@@ -228,3 +228,32 @@ fn can_set_fifo_mode() {
     sensor.acc_set_fifo_mode(FifoMode::Bypass, 0).unwrap();
     destroy_i2c(sensor);
 }
+
+#[test]
+fn can_set_accel_axes() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG1_A, 0b100]),
+        // Axes are kept when changing the mode
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG1_A, 0b0100_0100]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG1_A, 0b0100_0011]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG1_A, 0b0100_0000]),
+    ]);
+    assert_eq!(sensor.get_accel_axes(), AxisSet::all());
+
+    sensor.set_accel_axes(AxisSet::Z).unwrap();
+    assert_eq!(sensor.get_accel_axes(), AxisSet::Z);
+
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, Mode::Normal, ODR::Hz50)
+        .unwrap();
+    assert_eq!(sensor.get_accel_axes(), AxisSet::Z);
+
+    sensor.set_accel_axes(AxisSet::X | AxisSet::Y).unwrap();
+    assert_eq!(sensor.get_accel_axes(), AxisSet::X | AxisSet::Y);
+
+    sensor.set_accel_axes(AxisSet::empty()).unwrap();
+    assert_eq!(sensor.get_accel_axes(), AxisSet::empty());
+
+    destroy_i2c(sensor);
+}
@@ -0,0 +1,197 @@
+mod common;
+use crate::common::{
+    destroy_i2c, destroy_spi, BitFlags as BF, Register, ACCEL_ADDR, DEFAULT_CFG_REG_A_M,
+    DEFAULT_CTRL_REG1_A, HZ50, MAG_ADDR,
+};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::{
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+    spi::{Mock as SpiMock, Transaction as SpiTrans},
+};
+use lsm303agr::{
+    AccelMode, AccelScale, ClickConfig, Endianness, Error, Lsm303agr, MagMode, MagModeDevice,
+};
+
+const ACCEL_REGISTERS: [u8; 8] = [
+    Register::CTRL_REG1_A,
+    Register::CTRL_REG2_A,
+    Register::CTRL_REG3_A,
+    Register::CTRL_REG4_A,
+    Register::CTRL_REG5_A,
+    Register::CTRL_REG6_A,
+    Register::TEMP_CFG_REG_A,
+    Register::FIFO_CTRL_REG_A,
+];
+
+const MAG_REGISTERS: [u8; 4] = [
+    Register::CFG_REG_A_M,
+    Register::CFG_REG_B_M,
+    Register::CFG_REG_C_M,
+    Register::INT_CTRL_REG_M,
+];
+
+fn i2c_read_txns(accel: [u8; 8], mag: [u8; 4]) -> Vec<I2cTrans> {
+    let mut txns = vec![];
+    for (register, value) in ACCEL_REGISTERS.iter().zip(accel) {
+        txns.push(I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![*register],
+            vec![value],
+        ));
+    }
+    for (register, value) in MAG_REGISTERS.iter().zip(mag) {
+        txns.push(I2cTrans::write_read(MAG_ADDR, vec![*register], vec![value]));
+    }
+    txns
+}
+
+#[test]
+fn can_attach_i2c_one_shot() {
+    let mut txns = i2c_read_txns(
+        // Normal mode, 50 Hz, 8g, BOOT is ignored
+        [
+            HZ50 | DEFAULT_CTRL_REG1_A,
+            0,
+            0,
+            0b10 << 4,
+            BF::BOOT,
+            0,
+            0,
+            0,
+        ],
+        [DEFAULT_CFG_REG_A_M, 0, 0, 0xE0],
+    );
+    // The output data rate is known, so click detection can be configured.
+    txns.push(I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_THS_A, 0]));
+    txns.push(I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_LIMIT_A, 0]));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::TIME_LATENCY_A, 0],
+    ));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::TIME_WINDOW_A, 0],
+    ));
+    txns.push(I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_CFG_A, 0]));
+    // Cached CTRL_REG5_A
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG5_A, 0b01000000],
+    ));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::FIFO_CTRL_REG_A, 0b10000000],
+    ));
+
+    match Lsm303agr::attach_with_i2c(I2cMock::new(&txns))
+        .ok()
+        .unwrap()
+    {
+        MagModeDevice::OneShot(mut sensor) => {
+            assert_eq!(sensor.get_accel_mode(), AccelMode::Normal);
+            assert_eq!(sensor.get_accel_scale(), AccelScale::G8);
+            assert_eq!(sensor.get_mag_mode(), MagMode::HighResolution);
+            sensor.acc_set_click_detection(ClickConfig::new()).unwrap();
+            sensor
+                .acc_set_fifo_mode(lsm303agr::FifoMode::Stream, 0)
+                .unwrap();
+            destroy_i2c(sensor);
+        }
+        MagModeDevice::Continuous(_) => panic!("expected one-shot mode"),
+    }
+}
+
+#[test]
+fn can_attach_spi_continuous() {
+    let mut accel_txns = vec![];
+    for (register, value) in ACCEL_REGISTERS.iter().zip([0x07, 0, 0, BF::HR, 0, 0, 0, 0]) {
+        accel_txns.push(SpiTrans::transaction_start());
+        accel_txns.push(SpiTrans::transfer_in_place(
+            vec![BF::SPI_RW | register, 0],
+            vec![0, value],
+        ));
+        accel_txns.push(SpiTrans::transaction_end());
+    }
+    let mut mag_txns = vec![];
+    // Low-power, continuous mode
+    for (register, value) in MAG_REGISTERS.iter().zip([0b0001_0000, 0, 0, 0xE0]) {
+        mag_txns.push(SpiTrans::transaction_start());
+        mag_txns.push(SpiTrans::transfer_in_place(
+            vec![BF::SPI_RW | register, 0],
+            vec![0, value],
+        ));
+        mag_txns.push(SpiTrans::transaction_end());
+    }
+
+    let device = Lsm303agr::attach_with_spi(SpiMock::new(&accel_txns), SpiMock::new(&mag_txns));
+    match device.ok().unwrap() {
+        MagModeDevice::Continuous(mut sensor) => {
+            assert_eq!(sensor.get_accel_mode(), AccelMode::PowerDown);
+            assert_eq!(sensor.get_mag_mode(), MagMode::LowPower);
+            destroy_spi(sensor);
+        }
+        MagModeDevice::OneShot(_) => panic!("expected continuous mode"),
+    }
+}
+
+#[test]
+fn attach_keeps_big_endian_selection() {
+    let mut txns = i2c_read_txns(
+        // High-resolution mode, 50 Hz, big endian
+        [
+            HZ50 | DEFAULT_CTRL_REG1_A,
+            0,
+            0,
+            BF::HR | BF::ACCEL_BLE,
+            0,
+            0,
+            0,
+            0,
+        ],
+        // Continuous mode, big endian
+        [0, 0, BF::MAG_BLE, 0xE0],
+    );
+    txns.push(I2cTrans::write_read(
+        ACCEL_ADDR,
+        vec![Register::OUT_X_L_A | 0x80],
+        vec![0x20, 0x10, 0x40, 0x30, 0x60, 0x50],
+    ));
+    txns.push(I2cTrans::write_read(
+        MAG_ADDR,
+        vec![Register::OUTX_L_REG_M | 0x80],
+        vec![0x20, 0x10, 0x40, 0x30, 0x60, 0x50],
+    ));
+
+    match Lsm303agr::attach_with_i2c(I2cMock::new(&txns))
+        .ok()
+        .unwrap()
+    {
+        MagModeDevice::Continuous(mut sensor) => {
+            assert_eq!(sensor.get_accel_endianness(), Endianness::Big);
+            assert_eq!(sensor.get_mag_endianness(), Endianness::Big);
+            let data = sensor.acceleration().unwrap();
+            assert_eq!(data.xyz_raw(), (0x2010, 0x4030, 0x6050));
+            let data = sensor.magnetic_field().unwrap();
+            assert_eq!(data.xyz_raw(), (0x2010, 0x4030, 0x6050));
+            destroy_i2c(sensor);
+        }
+        MagModeDevice::OneShot(_) => panic!("expected continuous mode"),
+    }
+}
+
+#[test]
+fn attach_error_returns_device() {
+    let sensor = Lsm303agr::new_with_i2c(I2cMock::new(&[I2cTrans::write_read(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG1_A],
+        vec![0],
+    )
+    .with_error(ErrorKind::Other)]));
+    match sensor.attach() {
+        Err(e) => {
+            assert!(matches!(e.error, Error::Comm(ErrorKind::Other)));
+            destroy_i2c(e.dev);
+        }
+        Ok(_) => panic!("attach should fail"),
+    }
+}
@@ -0,0 +1,149 @@
+mod common;
+use crate::common::{
+    destroy_i2c, new_i2c, Register, ACCEL_ADDR, DEFAULT_CFG_REG_A_M, DEFAULT_CTRL_REG1_A, HZ50,
+    MAG_ADDR,
+};
+use embedded_hal_mock::eh1::{delay::NoopDelay as Delay, i2c::Transaction as I2cTrans};
+use lsm303agr::{
+    AccelMode, AccelOutputDataRate, AccelScale, DeviceConfig, FifoMode, MagMode, MagOutputDataRate,
+};
+
+// Low-power mode, 50 Hz
+const MAG_CFG_A: u8 = 0b0001_1000;
+
+fn configure_txns() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0b01 << 4]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, 0b0100_0000]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::FIFO_CTRL_REG_A, 0b1000_0101]),
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![Register::CFG_REG_A_M, MAG_CFG_A | DEFAULT_CFG_REG_A_M],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, 1]),
+    ]
+}
+
+fn configured_snapshot() -> DeviceConfig {
+    let mut sensor = new_i2c(&configure_txns());
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz50)
+        .unwrap();
+    sensor.set_accel_scale(AccelScale::G4).unwrap();
+    sensor.acc_set_fifo_mode(FifoMode::Stream, 5).unwrap();
+    sensor
+        .set_mag_mode_and_odr(&mut Delay, MagMode::LowPower, MagOutputDataRate::Hz50)
+        .unwrap();
+    sensor.mag_enable_low_pass_filter().unwrap();
+    let config = sensor.config();
+    destroy_i2c(sensor);
+    config
+}
+
+#[test]
+fn can_get_config() {
+    let config = configured_snapshot();
+    assert_eq!(config.accel_mode(), AccelMode::Normal);
+    assert_eq!(config.accel_odr(), Some(AccelOutputDataRate::Hz50));
+    assert_eq!(config.accel_scale(), AccelScale::G4);
+    assert_eq!(config.mag_mode(), MagMode::LowPower);
+    assert_eq!(config.mag_odr(), MagOutputDataRate::Hz50);
+    assert!(config.mag_low_pass_filter());
+    assert!(!config.mag_offset_cancellation());
+
+    let sensor = new_i2c(&[]);
+    assert_eq!(sensor.config(), DeviceConfig::default());
+    assert_eq!(DeviceConfig::default().accel_mode(), AccelMode::PowerDown);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_serialize_config() {
+    let config = configured_snapshot();
+    let bytes = config.to_bytes();
+    assert_eq!(
+        bytes,
+        [
+            1,
+            HZ50 | DEFAULT_CTRL_REG1_A,
+            0,
+            0,
+            0b01 << 4,
+            0b0100_0000,
+            0,
+            0,
+            0b1000_0101,
+            MAG_CFG_A | DEFAULT_CFG_REG_A_M,
+            1,
+            0,
+            0xE0,
+        ]
+    );
+    assert_eq!(DeviceConfig::from_bytes(&bytes), Some(config));
+
+    let mut bytes = bytes;
+    bytes[0] = 0xFF;
+    assert_eq!(DeviceConfig::from_bytes(&bytes), None);
+}
+
+#[test]
+fn can_apply_config() {
+    let config = configured_snapshot();
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0b01 << 4]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG3_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, 0b0100_0000]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::FIFO_CTRL_REG_A, 0b1000_0101]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TEMP_CFG_REG_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, 0]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, 1]),
+        I2cTrans::write(MAG_ADDR, vec![Register::INT_CTRL_REG_M, 0xE0]),
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![Register::CFG_REG_A_M, MAG_CFG_A | DEFAULT_CFG_REG_A_M],
+        ),
+    ]);
+    sensor.apply_config(&mut Delay, &config).unwrap();
+    assert_eq!(sensor.config(), config);
+    assert_eq!(sensor.get_accel_mode(), AccelMode::Normal);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn apply_config_keeps_mag_continuous_mode() {
+    let config = configured_snapshot();
+    let sensor = new_i2c(&[
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0b01 << 4]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG3_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, 0b0100_0000]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::FIFO_CTRL_REG_A, 0b1000_0101]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TEMP_CFG_REG_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, 0]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, 1]),
+        I2cTrans::write(MAG_ADDR, vec![Register::INT_CTRL_REG_M, 0xE0]),
+        // Continuous mode is kept
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, MAG_CFG_A]),
+    ]);
+    let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+    sensor.apply_config(&mut Delay, &config).unwrap();
+    assert_eq!(sensor.get_mag_mode(), MagMode::LowPower);
+    destroy_i2c(sensor);
+}
@@ -1,7 +1,7 @@
 mod common;
 use crate::common::{
-    destroy_i2c, destroy_spi, new_i2c, new_spi, new_spi_accel, new_spi_mag, BitFlags as BF,
-    Register, ACCEL_ADDR, MAG_ADDR,
+    destroy_i2c, destroy_spi, new_i2c, new_spi, new_spi_3wire, new_spi_accel, new_spi_mag,
+    BitFlags as BF, Register, ACCEL_ADDR, DEFAULT_CFG_REG_A_M, MAG_ADDR,
 };
 use embedded_hal_mock::eh1::{i2c::Transaction as I2cTrans, spi::Transaction as SpiTrans};
 
@@ -154,6 +154,13 @@ fn can_init_i2c() {
             vec![Register::TEMP_CFG_REG_A, BF::TEMP_EN1 | BF::TEMP_EN0],
         ),
         I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, BF::MAG_BDU]),
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ],
+        ),
     ]);
     sensor.init().unwrap();
     destroy_i2c(sensor);
@@ -174,8 +181,108 @@ fn can_init_spi() {
             SpiTrans::transaction_start(),
             SpiTrans::write_vec(vec![Register::CFG_REG_C_M, BF::MAG_BDU]),
             SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ]),
+            SpiTrans::transaction_end(),
+        ],
+    );
+    sensor.init().unwrap();
+    destroy_spi(sensor);
+}
+
+#[test]
+fn can_init_spi_with_mag_i2c_disabled() {
+    let sensor = new_spi(
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![Register::CTRL_REG4_A, BF::ACCEL_BDU]),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![Register::TEMP_CFG_REG_A, BF::TEMP_EN1 | BF::TEMP_EN0]),
+            SpiTrans::transaction_end(),
+        ],
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![Register::CFG_REG_C_M, BF::MAG_I2C_DIS | BF::MAG_BDU]),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ]),
+            SpiTrans::transaction_end(),
         ],
     );
+    let mut sensor = sensor.with_mag_i2c_disabled();
     sensor.init().unwrap();
     destroy_spi(sensor);
 }
+
+#[test]
+fn can_set_mag_i2c_enabled_spi() {
+    let mut sensor = new_spi_mag(&[
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![Register::CFG_REG_C_M, BF::MAG_I2C_DIS]),
+        SpiTrans::transaction_end(),
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![Register::CFG_REG_C_M, 0]),
+        SpiTrans::transaction_end(),
+    ]);
+    sensor.set_mag_i2c_enabled(false).unwrap();
+    sensor.set_mag_i2c_enabled(true).unwrap();
+    destroy_spi(sensor);
+}
+
+#[test]
+fn can_init_spi_3wire() {
+    let mut sensor = new_spi_3wire(
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![
+                Register::CTRL_REG4_A,
+                BF::ACCEL_BDU | BF::ACCEL_SPI_ENABLE,
+            ]),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![Register::TEMP_CFG_REG_A, BF::TEMP_EN1 | BF::TEMP_EN0]),
+            SpiTrans::transaction_end(),
+        ],
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![Register::CFG_REG_C_M, BF::MAG_BDU]),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ]),
+            SpiTrans::transaction_end(),
+        ],
+    );
+    sensor.init().unwrap();
+    destroy_spi(sensor);
+}
+
+#[test]
+fn spi_3wire_ids_are_read_half_duplex() {
+    let mut sensor = new_spi_3wire(
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![BF::SPI_RW | Register::WHO_AM_I_A]),
+            SpiTrans::read_vec(vec![0x33]),
+            SpiTrans::transaction_end(),
+        ],
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![BF::SPI_RW | Register::WHO_AM_I_M]),
+            SpiTrans::read_vec(vec![0x40]),
+            SpiTrans::transaction_end(),
+        ],
+    );
+    assert!(sensor.accelerometer_id().unwrap().is_correct());
+    assert!(sensor.magnetometer_id().unwrap().is_correct());
+    destroy_spi(sensor);
+}
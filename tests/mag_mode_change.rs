@@ -1,6 +1,9 @@
 mod common;
-use crate::common::{destroy_i2c, new_i2c, Register, MAG_ADDR};
-use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use crate::common::{
+    destroy_i2c, new_i2c, Register, ACCEL_ADDR, DEFAULT_CTRL_REG1_A, HZ50, MAG_ADDR,
+};
+use embedded_hal_mock::eh1::{delay::NoopDelay as Delay, i2c::Transaction as I2cTrans};
+use lsm303agr::{AccelMode, AccelOutputDataRate, ClickConfig};
 
 #[test]
 fn can_change_into_continuous() {
@@ -19,3 +22,28 @@ fn can_change_into_one_shot() {
     let sensor = sensor.into_mag_one_shot().ok().unwrap();
     destroy_i2c(sensor);
 }
+
+#[test]
+fn keeps_accel_odr_after_mode_change() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_THS_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_LIMIT_A, 1]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_LATENCY_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TIME_WINDOW_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CLICK_CFG_A, 0b0001_0000]),
+    ]);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz50)
+        .unwrap();
+    let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+    sensor
+        .acc_set_click_detection(ClickConfig::new().z_single(true).time_limit_ms(20))
+        .unwrap();
+    destroy_i2c(sensor);
+}
@@ -7,7 +7,10 @@ use crate::common::{
 use embedded_hal_mock::eh1::{
     delay::NoopDelay as Delay, i2c::Transaction as I2cTrans, spi::Transaction as SpiTrans,
 };
-use lsm303agr::{MagMode, MagOutputDataRate as ODR};
+use lsm303agr::{
+    calibration::MagCalibration, Endianness, InterruptPolarity, MagInterruptConfig, MagMode,
+    MagOutputDataRate as ODR, MagSetPulseFrequency,
+};
 
 macro_rules! set_mag_odr {
     ($name:ident, $hz:ident, $value:expr) => {
@@ -58,6 +61,14 @@ fn can_change_mode() {
 macro_rules! assert_eq_xyz_nt {
     ($data:expr) => {{
         crate::assert_eq_xyz!($data, x_nt, y_nt, z_nt, xyz_nt);
+        crate::assert_eq_xyz!(
+            $data,
+            x_microtesla,
+            y_microtesla,
+            z_microtesla,
+            xyz_microtesla
+        );
+        crate::assert_eq_xyz!($data, x_gauss, y_gauss, z_gauss, xyz_gauss);
     }};
 }
 
@@ -92,6 +103,15 @@ fn can_take_one_shot_measurement_i2c() {
     assert_eq!(data.y_nt(), 0x4030 * 150);
     assert_eq!(data.z_nt(), 0x6050 * 150);
 
+    let (x, y, z) = data.xyz_microtesla();
+    assert!((x - 1231.2).abs() < 1e-3);
+    assert!((y - 2464.8).abs() < 1e-3);
+    assert!((z - 3698.4).abs() < 1e-3);
+    let (x, y, z) = data.xyz_gauss();
+    assert!((x - 12.312).abs() < 1e-5);
+    assert!((y - 24.648).abs() < 1e-5);
+    assert!((z - 36.984).abs() < 1e-5);
+
     destroy_i2c(sensor);
 }
 
@@ -125,6 +145,28 @@ fn can_take_continuous_measurement_i2c() {
     destroy_i2c(sensor);
 }
 
+#[test]
+fn can_take_big_endian_measurement() {
+    let sensor = new_i2c(&[
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, 0]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, BF::MAG_BLE]),
+        I2cTrans::write_read(
+            MAG_ADDR,
+            vec![Register::OUTX_L_REG_M | 0x80],
+            vec![0x20, 0x10, 0x40, 0x30, 0x60, 0x50],
+        ),
+    ]);
+    let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+    assert_eq!(sensor.get_mag_endianness(), Endianness::Little);
+
+    sensor.set_mag_endianness(Endianness::Big).unwrap();
+    assert_eq!(sensor.get_mag_endianness(), Endianness::Big);
+    let data = sensor.magnetic_field().unwrap();
+    assert_eq!(data.xyz_raw(), (0x2010, 0x4030, 0x6050));
+
+    destroy_i2c(sensor);
+}
+
 #[test]
 fn can_take_continuous_measurement_spi() {
     let sensor = new_spi_mag(&[
@@ -267,3 +309,318 @@ fn can_disable_mag_low_pass_filter() {
 
     destroy_i2c(sensor);
 }
+
+#[test]
+fn can_set_mag_set_pulse_frequency() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, BF::MAG_SET_FREQ]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, 0]),
+    ]);
+    assert_eq!(
+        sensor.get_mag_set_pulse_frequency(),
+        MagSetPulseFrequency::Every63Odr
+    );
+
+    sensor
+        .set_mag_set_pulse_frequency(MagSetPulseFrequency::PowerOn)
+        .unwrap();
+    assert_eq!(
+        sensor.get_mag_set_pulse_frequency(),
+        MagSetPulseFrequency::PowerOn
+    );
+
+    sensor
+        .set_mag_set_pulse_frequency(MagSetPulseFrequency::Every63Odr)
+        .unwrap();
+    assert_eq!(
+        sensor.get_mag_set_pulse_frequency(),
+        MagSetPulseFrequency::Every63Odr
+    );
+
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_enable_and_disable_mag_interrupt_on_corrected_data() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![Register::CFG_REG_B_M, BF::MAG_INT_ON_DATA_OFF],
+        ),
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::CFG_REG_B_M,
+                BF::MAG_INT_ON_DATA_OFF | BF::MAG_SET_FREQ,
+            ],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, BF::MAG_SET_FREQ]),
+    ]);
+    sensor.mag_enable_interrupt_on_corrected_data().unwrap();
+    sensor
+        .set_mag_set_pulse_frequency(MagSetPulseFrequency::PowerOn)
+        .unwrap();
+    sensor.mag_disable_interrupt_on_corrected_data().unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_enable_mag_temperature_compensation() {
+    let mut sensor = new_i2c(&[I2cTrans::write(
+        MAG_ADDR,
+        vec![
+            Register::CFG_REG_A_M,
+            BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+        ],
+    )]);
+    sensor.enable_mag_temperature_compensation().unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_disable_mag_temperature_compensation() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, DEFAULT_CFG_REG_A_M]),
+    ]);
+    sensor.enable_mag_temperature_compensation().unwrap();
+    sensor.disable_mag_temperature_compensation().unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn mag_temperature_compensation_is_kept_when_setting_mode_and_odr() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ],
+        ),
+        // Set low-power mode, 100 Hz
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M | 0b00011100,
+            ],
+        ),
+    ]);
+    sensor.enable_mag_temperature_compensation().unwrap();
+    sensor
+        .set_mag_mode_and_odr(&mut Delay, MagMode::LowPower, ODR::Hz100)
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn mag_temperature_compensation_is_kept_when_changing_mode() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ],
+        ),
+        // Continuous mode
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, BF::COMP_TEMP_EN]),
+        // Idle mode
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::CFG_REG_A_M,
+                BF::COMP_TEMP_EN | DEFAULT_CFG_REG_A_M,
+            ],
+        ),
+        I2cTrans::write_read(MAG_ADDR, vec![Register::STATUS_REG_M], vec![0]),
+        I2cTrans::write_read(MAG_ADDR, vec![Register::CFG_REG_A_M], vec![0]),
+        // Single mode
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, BF::COMP_TEMP_EN | 1]),
+    ]);
+    sensor.enable_mag_temperature_compensation().unwrap();
+    let sensor = sensor.into_mag_continuous().ok().unwrap();
+    let mut sensor = sensor.into_mag_one_shot().ok().unwrap();
+    assert!(matches!(
+        sensor.magnetic_field(),
+        Err(nb::Error::WouldBlock)
+    ));
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_set_mag_hard_iron_offset_raw() {
+    let mut sensor = new_i2c(&[I2cTrans::write(
+        MAG_ADDR,
+        vec![
+            Register::OFFSET_X_REG_L_M | 0x80,
+            0x34,
+            0x12,
+            0xFF,
+            0xFF,
+            0x00,
+            0x80,
+        ],
+    )]);
+    sensor
+        .set_mag_hard_iron_offset_raw(0x1234, -1, i16::MIN)
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_set_mag_hard_iron_offset_nt_spi() {
+    let mut sensor = new_spi_mag(&[
+        SpiTrans::transaction_start(),
+        // 15000 nT = 100 LSB, -224 nT = -1 LSB, clamped to i16::MAX
+        SpiTrans::write_vec(vec![
+            BF::SPI_MS | Register::OFFSET_X_REG_L_M,
+            100,
+            0,
+            0xFF,
+            0xFF,
+            0xFF,
+            0x7F,
+        ]),
+        SpiTrans::transaction_end(),
+    ]);
+    sensor
+        .set_mag_hard_iron_offset_nt(15_000, -224, i32::MAX)
+        .unwrap();
+    destroy_spi(sensor);
+}
+
+#[test]
+fn can_set_mag_calibration() {
+    let mut sensor = new_i2c(&[I2cTrans::write(
+        MAG_ADDR,
+        vec![
+            Register::OFFSET_X_REG_L_M | 0x80,
+            100,
+            0,
+            0xFF,
+            0xFF,
+            0x34,
+            0x12,
+        ],
+    )]);
+    let calibration = MagCalibration::new((100, -1, 0x1234), (1.0, 1.0, 1.0));
+    sensor.set_mag_calibration(&calibration).unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_get_mag_hard_iron_offset() {
+    let mut sensor = new_i2c(&[I2cTrans::write_read(
+        MAG_ADDR,
+        vec![Register::OFFSET_X_REG_L_M | 0x80],
+        vec![100, 0, 0xFF, 0xFF, 0, 0],
+    )]);
+    let offset = sensor.mag_hard_iron_offset().unwrap();
+    assert_eq!(offset.xyz_unscaled(), (100, -1, 0));
+    assert_eq!(offset.xyz_nt(), (15_000, -150, 0));
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_set_mag_hard_iron_offset_in_continuous_mode() {
+    let sensor = new_i2c(&[
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, 0]),
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![Register::OFFSET_X_REG_L_M | 0x80, 1, 0, 2, 0, 3, 0],
+        ),
+    ]);
+    let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+    sensor.set_mag_hard_iron_offset_raw(1, 2, 3).unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_set_mag_interrupt() {
+    let mut sensor = new_i2c(&[
+        // 100000 nT / 150 nT = 666.67 -> 667
+        I2cTrans::write(MAG_ADDR, vec![Register::INT_THS_L_REG_M | 0x80, 0x9B, 0x02]),
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![
+                Register::INT_CTRL_REG_M,
+                BF::XIEN | BF::ZIEN | BF::IEL | BF::IEN,
+            ],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, BF::INT_MAG_PIN]),
+    ]);
+    let config = MagInterruptConfig::new()
+        .x(true)
+        .z(true)
+        .threshold_nt(100_000)
+        .polarity(InterruptPolarity::ActiveLow)
+        .latched(true)
+        .int_mag_pin(true);
+    sensor.mag_set_interrupt(config).unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_disable_mag_interrupt_spi() {
+    let mut sensor = new_spi_mag(&[
+        SpiTrans::transaction_start(),
+        // clamped to 32767
+        SpiTrans::write_vec(vec![BF::SPI_MS | Register::INT_THS_L_REG_M, 0xFF, 0x7F]),
+        SpiTrans::transaction_end(),
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![Register::INT_CTRL_REG_M, BF::IEA]),
+        SpiTrans::transaction_end(),
+        SpiTrans::transaction_start(),
+        SpiTrans::write_vec(vec![Register::CFG_REG_C_M, 0]),
+        SpiTrans::transaction_end(),
+    ]);
+    let config = MagInterruptConfig::new().threshold_nt(u32::MAX);
+    sensor.mag_set_interrupt(config).unwrap();
+    destroy_spi(sensor);
+}
+
+#[test]
+fn can_read_mag_interrupt_source() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write_read(
+            MAG_ADDR,
+            vec![Register::INT_SOURCE_REG_M],
+            vec![0b1000_1001],
+        ),
+        I2cTrans::write(MAG_ADDR, vec![Register::INT_THS_L_REG_M | 0x80, 0, 0]),
+        I2cTrans::write(MAG_ADDR, vec![Register::INT_CTRL_REG_M, BF::YIEN | BF::IEN]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, 0]),
+        I2cTrans::write_read(
+            MAG_ADDR,
+            vec![Register::INT_SOURCE_REG_M],
+            vec![0b0000_0010],
+        ),
+    ]);
+    let source = sensor.mag_interrupt_source().unwrap();
+    // IEA is cleared after reset, so INT = 1 signals no interrupt.
+    assert!(!source.active());
+    assert!(source.x_positive());
+    assert!(!source.y_positive());
+    assert!(!source.z_positive());
+    assert!(!source.x_negative());
+    assert!(source.y_negative());
+    assert!(!source.z_negative());
+    assert!(!source.overflow());
+
+    let config = MagInterruptConfig::new()
+        .y(true)
+        .polarity(InterruptPolarity::ActiveLow);
+    sensor.mag_set_interrupt(config).unwrap();
+    let source = sensor.mag_interrupt_source().unwrap();
+    assert!(source.active());
+    assert!(source.overflow());
+    destroy_i2c(sensor);
+}
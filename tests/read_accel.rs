@@ -6,7 +6,9 @@ use crate::common::{
 use embedded_hal_mock::eh1::{
     delay::NoopDelay as Delay, i2c::Transaction as I2cTrans, spi::Transaction as SpiTrans,
 };
-use lsm303agr::{AccelMode, AccelOutputDataRate, AccelScale};
+use lsm303agr::{
+    calibration::AccelCalibration, AccelMode, AccelOutputDataRate, AccelScale, AxisSet, Endianness,
+};
 
 fn i2c_mode_txns(mode: &AccelMode) -> Vec<I2cTrans> {
     match mode {
@@ -147,6 +149,8 @@ macro_rules! measurement_almost_eq {
 macro_rules! assert_eq_xyz_mg {
     ($data:expr) => {{
         crate::assert_eq_xyz!($data, x_mg, y_mg, z_mg, xyz_mg);
+        crate::assert_eq_xyz!($data, x_g, y_g, z_g, xyz_g);
+        crate::assert_eq_xyz!($data, x_mps2, y_mps2, z_mps2, xyz_mps2);
     }};
 }
 
@@ -192,6 +196,15 @@ fn can_get_8_bit_data_i2c() {
         0x6050 / (1 << 4),
         16
     );
+
+    let (x, y, z) = data.xyz_g();
+    assert!((x - 0.512).abs() < 1e-6);
+    assert!((y - 1.024).abs() < 1e-6);
+    assert!((z - 1.536).abs() < 1e-6);
+    let (x, y, z) = data.xyz_mps2();
+    assert!((x - 0.512 * 9.80665).abs() < 1e-5);
+    assert!((y - 1.024 * 9.80665).abs() < 1e-5);
+    assert!((z - 1.536 * 9.80665).abs() < 1e-5);
     destroy_i2c(sensor);
 }
 
@@ -331,3 +344,115 @@ fn can_get_12_bit_data_i2c() {
 
     destroy_i2c(sensor);
 }
+
+#[test]
+fn acceleration_reports_enabled_axes() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG1_A, 0b001]),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            vec![0x10, 0x20, 0, 0, 0, 0],
+        ),
+    ]);
+    sensor.set_accel_axes(AxisSet::X).unwrap();
+    let data = sensor.acceleration().unwrap();
+
+    assert_eq!(data.axes(), AxisSet::X);
+    assert_eq!(data.x_raw(), 0x2010);
+
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_read_big_endian_acceleration() {
+    let mut transactions = i2c_mode_txns(&AccelMode::HighResolution);
+    transactions.extend([
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG4_A, BF::HR | BF::ACCEL_BLE],
+        ),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            vec![0x20, 0x10, 0x40, 0x30, 0x60, 0x50],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, BF::HR]),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            vec![0x10, 0x20, 0x30, 0x40, 0x50, 0x60],
+        ),
+    ]);
+    let mut sensor = new_i2c(&transactions);
+    sensor
+        .set_accel_mode_and_odr(
+            &mut Delay,
+            AccelMode::HighResolution,
+            AccelOutputDataRate::Hz50,
+        )
+        .unwrap();
+    assert_eq!(sensor.get_accel_endianness(), Endianness::Little);
+
+    sensor.set_accel_endianness(Endianness::Big).unwrap();
+    assert_eq!(sensor.get_accel_endianness(), Endianness::Big);
+    let data = sensor.acceleration().unwrap();
+    assert_eq!(data.xyz_raw(), (0x2010, 0x4030, 0x6050));
+
+    sensor.set_accel_endianness(Endianness::Little).unwrap();
+    let data = sensor.acceleration().unwrap();
+    assert_eq!(data.xyz_raw(), (0x2010, 0x4030, 0x6050));
+
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn big_endian_selection_is_ignored_outside_high_resolution_mode() {
+    let mut transactions = i2c_mode_txns(&AccelMode::Normal);
+    transactions.extend([
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, BF::ACCEL_BLE]),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            vec![0x10, 0x20, 0x30, 0x40, 0x50, 0x60],
+        ),
+    ]);
+    let mut sensor = new_i2c(&transactions);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz50)
+        .unwrap();
+    sensor.set_accel_endianness(Endianness::Big).unwrap();
+    assert_eq!(sensor.get_accel_endianness(), Endianness::Big);
+    let data = sensor.acceleration().unwrap();
+    assert_eq!(data.xyz_raw(), (0x2010, 0x4030, 0x6050));
+
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_get_calibrated_data() {
+    let mut sensor = new_i2c(&[
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![
+                Register::CTRL_REG1_A,
+                BF::LP_EN | DEFAULT_CTRL_REG1_A | HZ50,
+            ],
+        ),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            vec![0x10, 0x20, 0x30, 0x40, 0x50, 0x60],
+        ),
+    ]);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::LowPower, AccelOutputDataRate::Hz50)
+        .unwrap();
+    // Measured: 512, 1024, 1536 mg
+    let calibration = AccelCalibration::new((12, 24, 36), (1.0, 0.5, 2.0));
+    let data = sensor.acceleration_calibrated(&calibration).unwrap();
+    assert_eq!(data, (500, 500, 3000));
+
+    destroy_i2c(sensor);
+}
@@ -0,0 +1,171 @@
+mod common;
+use crate::common::{
+    destroy_i2c, destroy_spi, new_i2c, new_spi, BitFlags as BF, Register, ACCEL_ADDR,
+    DEFAULT_CFG_REG_A_M, DEFAULT_CTRL_REG1_A, HZ50, MAG_ADDR,
+};
+use embedded_hal_mock::eh1::{i2c::Transaction as I2cTrans, spi::Transaction as SpiTrans};
+use lsm303agr::{AccelMode, AccelOutputDataRate, AccelScale, Error};
+
+#[test]
+fn can_read_accel_reg_i2c() {
+    let mut sensor = new_i2c(&[I2cTrans::write_read(
+        ACCEL_ADDR,
+        vec![Register::REFERENCE_A],
+        vec![0xAB],
+    )]);
+    assert_eq!(sensor.read_accel_reg(Register::REFERENCE_A).unwrap(), 0xAB);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_write_accel_reg_i2c() {
+    let mut sensor = new_i2c(&[I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::REFERENCE_A, 0xAB],
+    )]);
+    sensor.write_accel_reg(Register::REFERENCE_A, 0xAB).unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_read_mag_reg_i2c() {
+    let mut sensor = new_i2c(&[I2cTrans::write_read(
+        MAG_ADDR,
+        vec![Register::INT_SOURCE_REG_M],
+        vec![0x81],
+    )]);
+    assert_eq!(
+        sensor.read_mag_reg(Register::INT_SOURCE_REG_M).unwrap(),
+        0x81
+    );
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_write_mag_reg_i2c() {
+    let mut sensor = new_i2c(&[I2cTrans::write(
+        MAG_ADDR,
+        vec![Register::INT_CTRL_REG_M, 0xE1],
+    )]);
+    sensor
+        .write_mag_reg(Register::INT_CTRL_REG_M, 0xE1)
+        .unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_read_and_write_regs_spi() {
+    let mut sensor = new_spi(
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::transfer_in_place(vec![BF::SPI_RW | Register::REFERENCE_A, 0], vec![0, 0xAB]),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![Register::REFERENCE_A, 0xCD]),
+            SpiTrans::transaction_end(),
+        ],
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::transfer_in_place(
+                vec![BF::SPI_RW | Register::INT_SOURCE_REG_M, 0],
+                vec![0, 0x81],
+            ),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![Register::INT_CTRL_REG_M, 0xE1]),
+            SpiTrans::transaction_end(),
+        ],
+    );
+    assert_eq!(sensor.read_accel_reg(Register::REFERENCE_A).unwrap(), 0xAB);
+    sensor.write_accel_reg(Register::REFERENCE_A, 0xCD).unwrap();
+    assert_eq!(
+        sensor.read_mag_reg(Register::INT_SOURCE_REG_M).unwrap(),
+        0x81
+    );
+    sensor
+        .write_mag_reg(Register::INT_CTRL_REG_M, 0xE1)
+        .unwrap();
+    destroy_spi(sensor);
+}
+
+#[test]
+fn cannot_access_invalid_address() {
+    let mut sensor = new_i2c(&[]);
+    assert!(matches!(
+        sensor.read_accel_reg(0x40),
+        Err(Error::InvalidInputData)
+    ));
+    assert!(matches!(
+        sensor.write_accel_reg(0x40, 0),
+        Err(Error::InvalidInputData)
+    ));
+    assert!(matches!(
+        sensor.read_accel_reg(0x80),
+        Err(Error::InvalidInputData)
+    ));
+    assert!(matches!(
+        sensor.write_accel_reg(0x80, 0),
+        Err(Error::InvalidInputData)
+    ));
+    assert!(matches!(
+        sensor.read_mag_reg(0xE0),
+        Err(Error::InvalidInputData)
+    ));
+    assert!(matches!(
+        sensor.write_mag_reg(0xE0, 0),
+        Err(Error::InvalidInputData)
+    ));
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_refresh_cache() {
+    let accel = [
+        (Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A),
+        (Register::CTRL_REG2_A, 0),
+        (Register::CTRL_REG3_A, 0),
+        (Register::CTRL_REG4_A, 0b10 << 4),
+        (Register::CTRL_REG5_A, 0),
+        (Register::CTRL_REG6_A, 0),
+        (Register::TEMP_CFG_REG_A, 0),
+        (Register::FIFO_CTRL_REG_A, 0),
+    ];
+    let mag = [
+        (Register::CFG_REG_A_M, DEFAULT_CFG_REG_A_M),
+        (Register::CFG_REG_B_M, 0),
+        (Register::CFG_REG_C_M, 0),
+        (Register::INT_CTRL_REG_M, 0xE0),
+    ];
+    let mut txns = vec![
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0b10 << 4]),
+    ];
+    for (register, value) in accel {
+        txns.push(I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![register],
+            vec![value],
+        ));
+    }
+    for (register, value) in mag {
+        txns.push(I2cTrans::write_read(MAG_ADDR, vec![register], vec![value]));
+    }
+    let mut sensor = new_i2c(&txns);
+    sensor
+        .write_accel_reg(Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A)
+        .unwrap();
+    sensor
+        .write_accel_reg(Register::CTRL_REG4_A, 0b10 << 4)
+        .unwrap();
+    assert_eq!(sensor.get_accel_mode(), AccelMode::PowerDown);
+    assert_eq!(sensor.get_accel_scale(), AccelScale::G2);
+
+    sensor.refresh_cache().unwrap();
+    assert_eq!(sensor.get_accel_mode(), AccelMode::Normal);
+    assert_eq!(sensor.get_accel_scale(), AccelScale::G8);
+    assert_eq!(sensor.config().accel_odr(), Some(AccelOutputDataRate::Hz50));
+    destroy_i2c(sensor);
+}
@@ -0,0 +1,144 @@
+mod common;
+use crate::common::{
+    destroy_i2c, destroy_spi, new_i2c, new_spi, BitFlags as BF, Register, ACCEL_ADDR,
+    DEFAULT_CFG_REG_A_M, DEFAULT_CTRL_REG1_A, HZ50, MAG_ADDR,
+};
+use embedded_hal_mock::eh1::{i2c::Transaction as I2cTrans, spi::Transaction as SpiTrans};
+
+const ACCEL_FIRST: u8 = 0x07;
+const MAG_FIRST: u8 = 0x45;
+
+fn accel_registers() -> Vec<u8> {
+    let mut data = vec![0; 57];
+    data[(Register::WHO_AM_I_A - ACCEL_FIRST) as usize] = 0x33;
+    data[(Register::CTRL_REG1_A - ACCEL_FIRST) as usize] = HZ50 | DEFAULT_CTRL_REG1_A;
+    data[(Register::CTRL_REG4_A - ACCEL_FIRST) as usize] = BF::ACCEL_BDU | BF::HR;
+    data[(Register::INT1_CFG_A - ACCEL_FIRST) as usize] = BF::XHIE;
+    data[(Register::ACT_THS_A - ACCEL_FIRST) as usize] = 0x12;
+    data
+}
+
+fn accel_transactions(data: &[u8]) -> Vec<I2cTrans> {
+    let out = (Register::OUT_X_L_A - ACCEL_FIRST) as usize;
+    let after = (Register::FIFO_CTRL_REG_A - ACCEL_FIRST) as usize;
+    vec![
+        I2cTrans::write_read(ACCEL_ADDR, vec![0x80 | ACCEL_FIRST], data[..out].to_vec()),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![0x80 | Register::OUT_X_L_A],
+            data[out..after].to_vec(),
+        ),
+        I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![0x80 | Register::FIFO_CTRL_REG_A],
+            data[after..].to_vec(),
+        ),
+    ]
+}
+
+fn i2c_transactions(accel: &[u8]) -> Vec<I2cTrans> {
+    let mut transactions = accel_transactions(accel);
+    transactions.push(I2cTrans::write_read(
+        MAG_ADDR,
+        vec![0x80 | MAG_FIRST],
+        mag_registers(),
+    ));
+    transactions
+}
+
+fn mag_registers() -> Vec<u8> {
+    let mut data = vec![0; 43];
+    data[(Register::WHO_AM_I_M - MAG_FIRST) as usize] = 0x40;
+    data[(Register::CFG_REG_A_M - MAG_FIRST) as usize] = DEFAULT_CFG_REG_A_M;
+    data[(Register::INT_CTRL_REG_M - MAG_FIRST) as usize] = 0xE0;
+    data
+}
+
+#[test]
+fn can_dump_registers_i2c() {
+    let mut sensor = new_i2c(&i2c_transactions(&accel_registers()));
+    let dump = sensor.dump_registers().unwrap();
+    assert_eq!(dump.accel_registers(), accel_registers().as_slice());
+    assert_eq!(dump.mag_registers(), mag_registers().as_slice());
+    assert_eq!(dump.accel_register(Register::WHO_AM_I_A), Some(0x33));
+    assert_eq!(dump.accel_register(Register::ACT_DUR_A), Some(0));
+    assert_eq!(dump.accel_register(0x06), None);
+    assert_eq!(dump.accel_register(0x40), None);
+    assert_eq!(dump.mag_register(Register::WHO_AM_I_M), Some(0x40));
+    assert_eq!(dump.mag_register(0x6F), Some(0));
+    assert_eq!(dump.mag_register(0x44), None);
+    assert_eq!(dump.mag_register(0x70), None);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_dump_registers_spi() {
+    let mut sensor = new_spi(
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![BF::SPI_RW | BF::SPI_MS | ACCEL_FIRST]),
+            SpiTrans::read_vec(accel_registers()[..33].to_vec()),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![BF::SPI_RW | BF::SPI_MS | Register::OUT_X_L_A]),
+            SpiTrans::read_vec(accel_registers()[33..39].to_vec()),
+            SpiTrans::transaction_end(),
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![BF::SPI_RW | BF::SPI_MS | Register::FIFO_CTRL_REG_A]),
+            SpiTrans::read_vec(accel_registers()[39..].to_vec()),
+            SpiTrans::transaction_end(),
+        ],
+        &[
+            SpiTrans::transaction_start(),
+            SpiTrans::write_vec(vec![BF::SPI_RW | BF::SPI_MS | MAG_FIRST]),
+            SpiTrans::read_vec(mag_registers()),
+            SpiTrans::transaction_end(),
+        ],
+    );
+    let dump = sensor.dump_registers().unwrap();
+    assert_eq!(dump.accel_registers(), accel_registers().as_slice());
+    assert_eq!(dump.mag_registers(), mag_registers().as_slice());
+    destroy_spi(sensor);
+}
+
+#[test]
+fn can_dump_registers_with_fifo_enabled() {
+    let mut accel = accel_registers();
+    // FIFO_EN
+    accel[(Register::CTRL_REG5_A - ACCEL_FIRST) as usize] = 0b01000000;
+    accel[(Register::FIFO_CTRL_REG_A - ACCEL_FIRST) as usize] = 0x80;
+    for (i, value) in accel[33..39].iter_mut().enumerate() {
+        *value = 0x10 + i as u8;
+    }
+    let mut sensor = new_i2c(&i2c_transactions(&accel));
+    let dump = sensor.dump_registers().unwrap();
+    assert_eq!(dump.accel_registers(), accel.as_slice());
+    assert_eq!(dump.accel_register(Register::OUT_X_L_A), Some(0x10));
+    assert_eq!(dump.accel_register(0x2D), Some(0x15));
+    assert_eq!(dump.accel_register(Register::FIFO_CTRL_REG_A), Some(0x80));
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_display_register_dump() {
+    let mut sensor = new_i2c(&i2c_transactions(&accel_registers()));
+    let dump = sensor.dump_registers().unwrap();
+    let text = dump.to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "Accelerometer:");
+    assert_eq!(lines[1], "  0x07 STATUS_REG_AUX_A 0x00");
+    assert!(lines.contains(&"  0x0f WHO_AM_I_A       0x33"));
+    assert!(lines.contains(&"  0x20 CTRL_REG1_A      0x47 ODR2 | ZEN | YEN | XEN"));
+    assert!(lines.contains(&"  0x23 CTRL_REG4_A      0x88 BDU | HR"));
+    // Aliases are only listed once
+    assert!(lines.contains(&"  0x30 INT1_CFG_A       0x02 XHIE"));
+    assert!(lines.contains(&"  0x3e ACT_THS_A        0x12"));
+    assert!(lines.contains(&"Magnetometer:"));
+    assert!(lines.contains(&"  0x60 CFG_REG_A_M      0x03 MD1 | MD0"));
+    assert!(lines.contains(&"  0x63 INT_CTRL_REG_M   0xe0 XIEN | YIEN | ZIEN"));
+    assert_eq!(lines.last(), Some(&"  0x6d OUTZ_H_REG_M     0x00"));
+    // Reserved registers are not listed
+    assert!(!text.contains("0x08"));
+    assert!(!text.contains("0x6e"));
+    destroy_i2c(sensor);
+}
@@ -0,0 +1,139 @@
+mod common;
+use crate::common::{
+    destroy_i2c, new_i2c, BitFlags as BF, Register, ACCEL_ADDR, DEFAULT_CFG_REG_A_M,
+    DEFAULT_CTRL_REG1_A, HZ50, MAG_ADDR,
+};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::{delay::NoopDelay as Delay, i2c::Transaction as I2cTrans};
+use lsm303agr::{AccelMode, AccelOutputDataRate, Error, MagMode};
+
+fn accel_reset_txns() -> Vec<I2cTrans> {
+    let mut txns = vec![
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, BF::BOOT]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG1_A, DEFAULT_CTRL_REG1_A]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG3_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG5_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG6_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::TEMP_CFG_REG_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::FIFO_CTRL_REG_A, 0]),
+    ];
+    txns.extend(
+        ACCEL_INTERRUPT_REGISTERS
+            .iter()
+            .map(|register| I2cTrans::write(ACCEL_ADDR, vec![*register, 0])),
+    );
+    txns
+}
+
+const ACCEL_INTERRUPT_REGISTERS: [u8; 14] = [
+    Register::REFERENCE_A,
+    Register::INT1_CFG_A,
+    Register::INT1_THS_A,
+    Register::INT1_DURATION_A,
+    Register::INT2_CFG_A,
+    Register::INT2_THS_A,
+    Register::INT2_DURATION_A,
+    Register::CLICK_CFG_A,
+    Register::CLICK_THS_A,
+    Register::TIME_LIMIT_A,
+    Register::TIME_LATENCY_A,
+    Register::TIME_WINDOW_A,
+    Register::ACT_THS_A,
+    Register::ACT_DUR_A,
+];
+
+fn mag_reset_txns() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![Register::CFG_REG_A_M, BF::SOFT_RST | DEFAULT_CFG_REG_A_M],
+        ),
+        I2cTrans::write(
+            MAG_ADDR,
+            vec![Register::CFG_REG_A_M, BF::REBOOT | DEFAULT_CFG_REG_A_M],
+        ),
+    ]
+}
+
+#[test]
+fn can_reset_accel() {
+    let mut txns = vec![
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+    ];
+    txns.append(&mut accel_reset_txns());
+    let mut sensor = new_i2c(&txns);
+    sensor
+        .set_accel_mode_and_odr(&mut Delay, AccelMode::Normal, AccelOutputDataRate::Hz50)
+        .unwrap();
+    sensor.reset_accel(&mut Delay).unwrap();
+    assert_eq!(sensor.get_accel_mode(), AccelMode::PowerDown);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn reset_accel_clears_interrupt_configuration() {
+    let mut txns = vec![
+        I2cTrans::write(ACCEL_ADDR, vec![Register::ACT_DUR_A, 10]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::ACT_THS_A, 5]),
+    ];
+    txns.append(&mut accel_reset_txns());
+    let mut sensor = new_i2c(&txns);
+    sensor.set_activity_detection(80, 10).unwrap();
+    sensor.reset_accel(&mut Delay).unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_reset_mag_from_continuous() {
+    let mut txns = vec![
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, 0]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, BF::MAG_OFF_CANC]),
+    ];
+    txns.append(&mut mag_reset_txns());
+    // Cached configuration is back to the defaults.
+    txns.push(I2cTrans::write(
+        MAG_ADDR,
+        vec![
+            Register::CFG_REG_B_M,
+            BF::MAG_OFF_CANC | BF::MAG_OFF_CANC_ONE_SHOT,
+        ],
+    ));
+    let sensor = new_i2c(&txns);
+    let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+    sensor.enable_mag_offset_cancellation().unwrap();
+    let mut sensor = sensor.reset_mag(&mut Delay).ok().unwrap();
+    assert_eq!(sensor.get_mag_mode(), MagMode::HighResolution);
+    sensor.enable_mag_offset_cancellation().unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_reset_all() {
+    let mut txns = accel_reset_txns();
+    txns.append(&mut mag_reset_txns());
+    let sensor = new_i2c(&txns);
+    let sensor = sensor.reset_all(&mut Delay).ok().unwrap();
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn reset_mag_error_returns_device() {
+    let sensor = new_i2c(&[I2cTrans::write(
+        MAG_ADDR,
+        vec![Register::CFG_REG_A_M, BF::SOFT_RST | DEFAULT_CFG_REG_A_M],
+    )
+    .with_error(ErrorKind::Other)]);
+    match sensor.reset_mag(&mut Delay) {
+        Err(e) => {
+            assert!(matches!(e.error, Error::Comm(ErrorKind::Other)));
+            destroy_i2c(e.dev);
+        }
+        Ok(_) => panic!("reset should fail"),
+    }
+}
@@ -0,0 +1,352 @@
+mod common;
+use crate::common::{
+    destroy_i2c, destroy_spi, new_i2c, new_spi_mag, BitFlags as BF, Register, ACCEL_ADDR,
+    DEFAULT_CFG_REG_A_M, DEFAULT_CTRL_REG1_A, HZ50, MAG_ADDR,
+};
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, NoopDelay as Delay, Transaction as DelayTrans},
+    i2c::Transaction as I2cTrans,
+    spi::Transaction as SpiTrans,
+};
+use lsm303agr::{
+    AccelHighPassConfig, AccelHighPassMode, AccelMode, AccelOutputDataRate, AccelScale, MagMode,
+    MagOutputDataRate,
+};
+
+const HZ100: u8 = 5 << 4;
+const ST0: u8 = 1 << 1;
+const MAG_SELF_TEST: u8 = 1 << 1;
+// Temperature compensation, 100 Hz, continuous mode
+const MAG_SELF_TEST_CFG_A: u8 = 0x8C;
+
+fn accel_samples(x: i16, y: i16, z: i16) -> Vec<I2cTrans> {
+    let mut data = vec![];
+    data.extend_from_slice(&x.to_le_bytes());
+    data.extend_from_slice(&y.to_le_bytes());
+    data.extend_from_slice(&z.to_le_bytes());
+
+    let mut txns = vec![
+        // Data not yet available
+        I2cTrans::write_read(ACCEL_ADDR, vec![Register::STATUS_REG_A], vec![0]),
+    ];
+    // The first sample is discarded.
+    for _ in 0..6 {
+        txns.push(I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::STATUS_REG_A],
+            vec![BF::XYZDR],
+        ));
+        txns.push(I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::OUT_X_L_A | 0x80],
+            data.clone(),
+        ));
+    }
+    txns
+}
+
+#[test]
+fn can_run_accel_self_test() {
+    let mut txns = vec![
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, BF::ACCEL_BDU]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ100 | DEFAULT_CTRL_REG1_A],
+        ),
+    ];
+    txns.append(&mut accel_samples(0, 16 << 6, -(250 << 6)));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG4_A, BF::ACCEL_BDU | ST0],
+    ));
+    // 500 mg, 100 mg and 2000 mg difference at 4 mg/LSB
+    txns.append(&mut accel_samples(125 << 6, 41 << 6, 250 << 6));
+    // Restore
+    txns.push(I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG1_A, DEFAULT_CTRL_REG1_A],
+    ));
+    txns.push(I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]));
+    let mut sensor = new_i2c(&txns);
+
+    let report = sensor.accel_self_test(&mut Delay).unwrap();
+    assert_eq!(report.xyz_delta(), (500, 100, 2000));
+    assert_eq!(report.limits(), (68, 1440));
+    assert!(report.x_passed());
+    assert!(report.y_passed());
+    assert!(!report.z_passed());
+    assert!(!report.passed());
+    assert_eq!(sensor.get_accel_mode(), AccelMode::PowerDown);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn accel_self_test_restores_configuration() {
+    let mut txns = vec![
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+        ),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, BF::HR]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG4_A, BF::HR | (0b11 << 4)],
+        ),
+        // High-pass filter on output data
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0b1000_1000]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, BF::ACCEL_BDU]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ100 | DEFAULT_CTRL_REG1_A],
+        ),
+    ];
+    txns.append(&mut accel_samples(0, 0, 0));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG4_A, BF::ACCEL_BDU | ST0],
+    ));
+    txns.append(&mut accel_samples(100 << 6, -(100 << 6), 100 << 6));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG4_A, BF::HR | (0b11 << 4)],
+    ));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG1_A, HZ50 | DEFAULT_CTRL_REG1_A],
+    ));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG2_A, 0b1000_1000],
+    ));
+    let mut sensor = new_i2c(&txns);
+    sensor
+        .set_accel_mode_and_odr(
+            &mut Delay,
+            AccelMode::HighResolution,
+            AccelOutputDataRate::Hz50,
+        )
+        .unwrap();
+    sensor.set_accel_scale(AccelScale::G16).unwrap();
+    sensor
+        .acc_set_high_pass_filter(AccelHighPassConfig {
+            mode: AccelHighPassMode::Normal,
+            output_data: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_ms(90),
+        DelayTrans::delay_ms(1),
+        DelayTrans::delay_ms(90),
+        DelayTrans::delay_ms(1),
+        // Normal to high resolution mode at 50 Hz: 7/ODR
+        DelayTrans::delay_us(140),
+    ]);
+    let report = sensor.accel_self_test(&mut delay).unwrap();
+    assert_eq!(report.xyz_delta(), (400, 400, 400));
+    assert!(report.passed());
+    delay.done();
+
+    assert_eq!(sensor.get_accel_mode(), AccelMode::HighResolution);
+    assert_eq!(sensor.get_accel_scale(), AccelScale::G16);
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn accel_self_test_fails_without_new_data() {
+    let mut txns = vec![
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]),
+        I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, BF::ACCEL_BDU]),
+        I2cTrans::write(
+            ACCEL_ADDR,
+            vec![Register::CTRL_REG1_A, HZ100 | DEFAULT_CTRL_REG1_A],
+        ),
+    ];
+    for _ in 0..=100 {
+        txns.push(I2cTrans::write_read(
+            ACCEL_ADDR,
+            vec![Register::STATUS_REG_A],
+            vec![0],
+        ));
+    }
+    txns.push(I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG4_A, 0]));
+    txns.push(I2cTrans::write(
+        ACCEL_ADDR,
+        vec![Register::CTRL_REG1_A, DEFAULT_CTRL_REG1_A],
+    ));
+    txns.push(I2cTrans::write(ACCEL_ADDR, vec![Register::CTRL_REG2_A, 0]));
+    let mut sensor = new_i2c(&txns);
+
+    let report = sensor.accel_self_test(&mut Delay).unwrap();
+    assert_eq!(report.xyz_delta(), (0, 0, 0));
+    assert!(!report.passed());
+    destroy_i2c(sensor);
+}
+
+fn xyz_bytes(x: i16, y: i16, z: i16) -> Vec<u8> {
+    let mut data = vec![];
+    data.extend_from_slice(&x.to_le_bytes());
+    data.extend_from_slice(&y.to_le_bytes());
+    data.extend_from_slice(&z.to_le_bytes());
+    data
+}
+
+fn mag_samples(x: i16, y: i16, z: i16) -> Vec<I2cTrans> {
+    let mut txns = vec![I2cTrans::write_read(
+        MAG_ADDR,
+        vec![Register::STATUS_REG_M],
+        vec![0],
+    )];
+    // The first sample is discarded.
+    for _ in 0..51 {
+        txns.push(I2cTrans::write_read(
+            MAG_ADDR,
+            vec![Register::STATUS_REG_M],
+            vec![BF::XYZDR],
+        ));
+        txns.push(I2cTrans::write_read(
+            MAG_ADDR,
+            vec![Register::OUTX_L_REG_M | 0x80],
+            xyz_bytes(x, y, z),
+        ));
+    }
+    txns
+}
+
+#[test]
+fn can_run_mag_self_test_in_one_shot_mode() {
+    let mut txns = vec![
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, MAG_SELF_TEST_CFG_A]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, BF::MAG_OFF_CANC]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, BF::MAG_BDU]),
+    ];
+    txns.append(&mut mag_samples(100, -100, 0));
+    txns.push(I2cTrans::write(
+        MAG_ADDR,
+        vec![Register::CFG_REG_C_M, BF::MAG_BDU | MAG_SELF_TEST],
+    ));
+    // 1.5 mG/LSB: 150 mG, 15 mG and 600 mG difference
+    txns.append(&mut mag_samples(200, -90, -400));
+    // Restore
+    txns.push(I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, 0]));
+    txns.push(I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, 0]));
+    txns.push(I2cTrans::write(
+        MAG_ADDR,
+        vec![Register::CFG_REG_A_M, DEFAULT_CFG_REG_A_M],
+    ));
+    let mut sensor = new_i2c(&txns);
+
+    let report = sensor.mag_self_test(&mut Delay).unwrap();
+    assert_eq!(report.xyz_delta(), (150, 15, 600));
+    assert_eq!(report.limits(), (15, 500));
+    assert!(report.x_passed());
+    assert!(report.y_passed());
+    assert!(!report.z_passed());
+    assert!(!report.passed());
+    destroy_i2c(sensor);
+}
+
+#[test]
+fn can_run_mag_self_test_in_continuous_mode_spi() {
+    fn spi_samples(txns: &mut Vec<SpiTrans<u8>>, x: i16, y: i16, z: i16) {
+        let mut response = vec![0];
+        response.append(&mut xyz_bytes(x, y, z));
+        for _ in 0..51 {
+            txns.push(SpiTrans::transaction_start());
+            txns.push(SpiTrans::transfer_in_place(
+                vec![BF::SPI_RW | Register::STATUS_REG_M, 0],
+                vec![0, BF::XYZDR],
+            ));
+            txns.push(SpiTrans::transaction_end());
+            txns.push(SpiTrans::transaction_start());
+            txns.push(SpiTrans::transfer_in_place(
+                vec![
+                    BF::SPI_RW | BF::SPI_MS | Register::OUTX_L_REG_M,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                ],
+                response.clone(),
+            ));
+            txns.push(SpiTrans::transaction_end());
+        }
+    }
+    fn spi_write(txns: &mut Vec<SpiTrans<u8>>, register: u8, value: u8) {
+        txns.push(SpiTrans::transaction_start());
+        txns.push(SpiTrans::write_vec(vec![register, value]));
+        txns.push(SpiTrans::transaction_end());
+    }
+
+    // Low-power, 50 Hz
+    let cfg_a = 0b0001_1000 | DEFAULT_CFG_REG_A_M;
+    let mut txns = vec![];
+    spi_write(&mut txns, Register::CFG_REG_A_M, cfg_a);
+    spi_write(&mut txns, Register::CFG_REG_A_M, cfg_a & !0b11);
+    spi_write(&mut txns, Register::CFG_REG_A_M, MAG_SELF_TEST_CFG_A);
+    spi_write(&mut txns, Register::CFG_REG_B_M, BF::MAG_OFF_CANC);
+    spi_write(&mut txns, Register::CFG_REG_C_M, BF::MAG_BDU);
+    spi_samples(&mut txns, 0, 0, 0);
+    spi_write(
+        &mut txns,
+        Register::CFG_REG_C_M,
+        BF::MAG_BDU | MAG_SELF_TEST,
+    );
+    spi_samples(&mut txns, 100, 100, 100);
+    spi_write(&mut txns, Register::CFG_REG_C_M, 0);
+    spi_write(&mut txns, Register::CFG_REG_B_M, 0);
+    spi_write(&mut txns, Register::CFG_REG_A_M, cfg_a & !0b11);
+
+    let mut sensor = new_spi_mag(&txns);
+    sensor
+        .set_mag_mode_and_odr(&mut Delay, MagMode::LowPower, MagOutputDataRate::Hz50)
+        .unwrap();
+    let mut sensor = sensor.into_mag_continuous().ok().unwrap();
+
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_ms(20),
+        DelayTrans::delay_ms(60),
+        // Low-power mode turn-on time
+        DelayTrans::delay_us(9400),
+    ]);
+    let report = sensor.mag_self_test(&mut delay).unwrap();
+    assert_eq!(report.xyz_delta(), (150, 150, 150));
+    assert!(report.passed());
+    delay.done();
+    assert_eq!(sensor.get_mag_mode(), MagMode::LowPower);
+    destroy_spi(sensor);
+}
+
+#[test]
+fn mag_self_test_fails_without_new_data() {
+    let mut txns = vec![
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_A_M, MAG_SELF_TEST_CFG_A]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, BF::MAG_OFF_CANC]),
+        I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, BF::MAG_BDU]),
+    ];
+    for _ in 0..=100 {
+        txns.push(I2cTrans::write_read(
+            MAG_ADDR,
+            vec![Register::STATUS_REG_M],
+            vec![0],
+        ));
+    }
+    txns.push(I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_C_M, 0]));
+    txns.push(I2cTrans::write(MAG_ADDR, vec![Register::CFG_REG_B_M, 0]));
+    txns.push(I2cTrans::write(
+        MAG_ADDR,
+        vec![Register::CFG_REG_A_M, DEFAULT_CFG_REG_A_M],
+    ));
+    let mut sensor = new_i2c(&txns);
+
+    let report = sensor.mag_self_test(&mut Delay).unwrap();
+    assert_eq!(report.xyz_delta(), (0, 0, 0));
+    assert!(!report.passed());
+    destroy_i2c(sensor);
+}